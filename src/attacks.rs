@@ -0,0 +1,103 @@
+// Precomputed attack tables
+//
+// Knights, kings and pawns attack the same squares from a given square no
+// matter what else is on the board, so their attacks are worked out once at
+// compile time. Index with the square number (a1 = 0 .. h8 = 63), pawn attacks
+// additionally with the color of the pawn (Color::White as usize, ..).
+//
+// BETWEEN and LINE describe the geometry used for pins and check blocking:
+// BETWEEN[a][b] are the squares strictly between a and b and LINE[a][b] the
+// whole rank, file or diagonal through both, both are empty when a and b
+// don't share one.
+
+use crate::chess_move::Color;
+use crate::chessboard::chessboard::Constants;
+
+// (rank offset, file offset)
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [(2, 1), (2, -1), (-2, 1), (-2, -1), (1, 2), (1, -2), (-1, 2), (-1, -2)];
+const KING_OFFSETS: [(i32, i32); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+// all squares reachable from square with the given offsets, ignoring ones that leave the board
+const fn attacks_from_offsets(square: usize, offsets: &[(i32, i32)]) -> u64 {
+    let rank = (square / 8) as i32;
+    let file = (square % 8) as i32;
+    let mut attacks: u64 = 0;
+    let mut i = 0;
+    while i < offsets.len() {
+        let (to_rank, to_file) = (rank + offsets[i].0, file + offsets[i].1);
+        if to_rank >= 0 && to_rank < 8 && to_file >= 0 && to_file < 8 {
+            attacks |= 1 << (to_rank * 8 + to_file);
+        }
+        i += 1;
+    }
+    return attacks;
+}
+
+const fn generate_table(offsets: &[(i32, i32)]) -> [u64; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        table[square] = attacks_from_offsets(square, offsets);
+        square += 1;
+    }
+    return table;
+}
+
+const fn generate_pawn_table() -> [[u64; 64]; 2] {
+    let mut table = [[0; 64]; 2];
+    let mut square = 0;
+    while square < 64 {
+        let pawn: u64 = 1 << square;
+        // diagonal captures must not wrap around to the other side of the board
+        table[Color::White as usize][square] = ((pawn << 9) & !Constants::A_FILE) | ((pawn << 7) & !Constants::H_FILE);
+        table[Color::Black as usize][square] = ((pawn >> 7) & !Constants::A_FILE) | ((pawn >> 9) & !Constants::H_FILE);
+        square += 1;
+    }
+    return table;
+}
+
+// squares from square (exclusive) to the edge of the board in one direction
+const fn ray(square: usize, direction: (i32, i32)) -> u64 {
+    let (mut rank, mut file) = ((square / 8) as i32 + direction.0, (square % 8) as i32 + direction.1);
+    let mut result: u64 = 0;
+    while rank >= 0 && rank < 8 && file >= 0 && file < 8 {
+        result |= 1 << (rank * 8 + file);
+        rank += direction.0;
+        file += direction.1;
+    }
+    return result;
+}
+
+const fn generate_between_and_line() -> ([[u64; 64]; 64], [[u64; 64]; 64]) {
+    let mut between = [[0; 64]; 64];
+    let mut line = [[0; 64]; 64];
+    let mut from = 0;
+    while from < 64 {
+        let mut i = 0;
+        // the king steps are exactly the eight ray directions
+        while i < KING_OFFSETS.len() {
+            let forward = ray(from, KING_OFFSETS[i]);
+            let backward = ray(from, (-KING_OFFSETS[i].0, -KING_OFFSETS[i].1));
+            let mut to = 0;
+            while to < 64 {
+                if forward & (1 << to) > 0 {
+                    // the part of the ray that doesn't go past to
+                    between[from][to] = forward & !ray(to, KING_OFFSETS[i]) & !(1 << to);
+                    line[from][to] = forward | backward | (1 << from);
+                }
+                to += 1;
+            }
+            i += 1;
+        }
+        from += 1;
+    }
+    return (between, line);
+}
+
+const BETWEEN_AND_LINE: ([[u64; 64]; 64], [[u64; 64]; 64]) = generate_between_and_line();
+
+pub static KNIGHT_ATTACKS: [u64; 64] = generate_table(&KNIGHT_OFFSETS);
+pub static KING_ATTACKS: [u64; 64] = generate_table(&KING_OFFSETS);
+pub static PAWN_ATTACKS: [[u64; 64]; 2] = generate_pawn_table();
+pub(crate) static BETWEEN: [[u64; 64]; 64] = BETWEEN_AND_LINE.0;
+pub(crate) static LINE: [[u64; 64]; 64] = BETWEEN_AND_LINE.1;
@@ -0,0 +1,106 @@
+// Move representation
+//
+// A move only stores the squares it connects (using the square numbering from
+// chessboard.rs, a1 = 0 .. h8 = 63), the piece a pawn promotes to and a few
+// flags. Everything else (which piece moves, what gets captured) is looked up
+// on the ChessBoard when the move is made.
+//
+// Displaying a move gives the long algebraic notation UCI uses: starting and
+// destination square plus a lowercase promotion piece (e2e4, e7e8q), castling
+// is written as the king move (e1g1).
+
+use std::fmt;
+
+use crate::chessboard::chessboard::EnumSquare;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Piece {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Color {
+    White,
+    Black,
+}
+
+// Pieces a pawn can promote to, strongest first
+pub const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+
+pub struct MoveFlags;
+impl MoveFlags {
+    pub const QUIET: u8 = 0;
+    pub const CAPTURE: u8 = 1;
+    pub const EN_PASSANT: u8 = 2;
+    pub const CASTLE: u8 = 4;
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Move {
+    pub from: u8,
+    pub to: u8,
+    pub promotion: Option<Piece>,
+    pub flags: u8,
+}
+
+impl Move {
+    pub fn new(from: u8, to: u8, flags: u8) -> Self {
+        Self { from, to, promotion: None, flags }
+    }
+
+    // Generators work with single bit squares, so accept those directly
+    pub fn from_bitboards(from_square: u64, to_square: u64, flags: u8) -> Self {
+        Self::new(from_square.trailing_zeros() as u8, to_square.trailing_zeros() as u8, flags)
+    }
+
+    pub fn with_promotion(mut self, piece: Piece) -> Self {
+        self.promotion = Some(piece);
+        return self;
+    }
+
+    pub fn get_from_square(&self) -> u64 {
+        1 << self.from
+    }
+
+    pub fn get_to_square(&self) -> u64 {
+        1 << self.to
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.flags & (MoveFlags::CAPTURE | MoveFlags::EN_PASSANT) > 0
+    }
+
+    pub fn is_en_passant(&self) -> bool {
+        self.flags & MoveFlags::EN_PASSANT > 0
+    }
+
+    pub fn is_castle(&self) -> bool {
+        self.flags & MoveFlags::CASTLE > 0
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        self.promotion.is_some()
+    }
+
+    pub fn to_uci(&self) -> String {
+        return self.to_string();
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", EnumSquare::ALL[self.from as usize], EnumSquare::ALL[self.to as usize])?;
+        match self.promotion {
+            Some(Piece::Knight) => write!(f, "n"),
+            Some(Piece::Bishop) => write!(f, "b"),
+            Some(Piece::Rook) => write!(f, "r"),
+            Some(Piece::Queen) => write!(f, "q"),
+            _ => Ok(()),
+        }
+    }
+}
@@ -20,32 +20,25 @@
 //    +----+----+----+----+----+----+----+----+
 //       A    B    C    D    E    F    G    H - file(s)
 
-#![allow(non_camel_case_types, dead_code)]
+#![allow(non_camel_case_types, dead_code, clippy::module_inception)]
 
 pub mod chessboard {
     use std::collections::HashMap;
-    use std::num;
-    use std::result;
+    use std::fmt;
+    use std::hash::{Hash, Hasher};
     use std::vec;
 
+    use crate::attacks::{BETWEEN, KING_ATTACKS, KNIGHT_ATTACKS, LINE, PAWN_ATTACKS};
+    use crate::chess_move::*;
+    use crate::magic::{bishop_attacks, queen_attacks, rook_attacks};
+    use crate::zobrist::ZOBRIST_KEYS;
     use crate::white_utils::*;
     use crate::black_utils::*;
 
-    pub(crate) trait OverflowingLeftShift {
-        fn overflowing_loss_checked_shl(self, rhs: u32) -> (Self, bool) 
-            where Self: std::marker::Sized;
-    }
-
-    impl OverflowingLeftShift for u64 {
-        fn overflowing_loss_checked_shl(self, rhs: u32) -> (Self, bool) {
-            if rhs <= self.leading_zeros() {
-                return (self << rhs, false);
-            } else {
-                return (0, true);
-            }
-        }
-    }
-    enum EnumSquare {
+    // Square names in the numbering drawn above, EnumSquare::e4 as u8 == 28
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[repr(u8)]
+    pub enum EnumSquare {
         a1, b1, c1, d1, e1, f1, g1, h1, //  0 ..  7
         a2, b2, c2, d2, e2, f2, g2, h2, //  8 .. 15
         a3, b3, c3, d3, e3, f3, g3, h3, // 16 .. 23
@@ -55,60 +48,221 @@ pub mod chessboard {
         a7, b7, c7, d7, e7, f7, g7, h7, // 48 .. 55
         a8, b8, c8, d8, e8, f8, g8, h8  // 56 .. 63
     }
-      
-    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+
+    impl EnumSquare {
+        // indexed by square number
+        pub const ALL: [EnumSquare; 64] = {
+            use EnumSquare::*;
+            [
+                a1, b1, c1, d1, e1, f1, g1, h1,
+                a2, b2, c2, d2, e2, f2, g2, h2,
+                a3, b3, c3, d3, e3, f3, g3, h3,
+                a4, b4, c4, d4, e4, f4, g4, h4,
+                a5, b5, c5, d5, e5, f5, g5, h5,
+                a6, b6, c6, d6, e6, f6, g6, h6,
+                a7, b7, c7, d7, e7, f7, g7, h7,
+                a8, b8, c8, d8, e8, f8, g8, h8,
+            ]
+        };
+
+        pub fn from_index(index: u8) -> Option<EnumSquare> {
+            return Self::ALL.get(index as usize).copied();
+        }
+
+        // "e3" -> EnumSquare::e3
+        pub fn from_name(name: &str) -> Option<EnumSquare> {
+            let bytes = name.as_bytes();
+            if bytes.len() != 2 || !(b'a'..=b'h').contains(&bytes[0]) || !(b'1'..=b'8').contains(&bytes[1]) {
+                return None;
+            }
+            return Self::from_index((bytes[1] - b'1') * 8 + (bytes[0] - b'a'));
+        }
+
+        pub fn index(self) -> u8 {
+            self as u8
+        }
+
+        pub fn bitboard(self) -> u64 {
+            1 << self as u8
+        }
+
+        pub fn file(self) -> u8 {
+            self as u8 % 8
+        }
+
+        pub fn rank(self) -> u8 {
+            self as u8 / 8
+        }
+    }
+
+    impl fmt::Display for EnumSquare {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}{}", (b'a' + self.file()) as char, (b'1' + self.rank()) as char)
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     pub struct ChessBoard {
 
         pub white_to_move: bool,
-        pub prev_pos_pawns: u64,
+        // square skipped by a double pawn push on the previous move
+        pub en_passant_square: Option<u8>,
+
+        // CastlingRights bits still available to both sides
+        pub castling_rights: u8,
 
-        pub white_moved_king: bool,
-        pub white_moved_A_rook: bool,
-        pub white_moved_H_rook: bool,
         pub white_pawns: u64,
         pub white_rooks: u64,
         pub white_knights: u64,
         pub white_bishops: u64,
         pub white_queens: u64,
         pub white_king: u64,
-    
-        pub black_moved_king: bool,
-        pub black_moved_A_rook: bool,
-        pub black_moved_H_rook: bool,
+
         pub black_pawns: u64,
         pub black_rooks: u64,
         pub black_knights: u64,
         pub black_bishops: u64,
         pub black_queens: u64,
         pub black_king: u64,
+
+        pub halfmove_clock: u32,
+        pub fullmove_number: u32,
+
+        // kept up to date by make_move, see zobrist.rs
+        pub zobrist_key: u64,
     }
-    
+
+    // Everything make_move overwrites that can't be recomputed from the move itself
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct UndoInfo {
+        pub captured: Option<Piece>,
+        pub en_passant_square: Option<u8>,
+        pub castling_rights: u8,
+        pub halfmove_clock: u32,
+        pub zobrist_key: u64,
+    }
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub(crate) struct CheckInfo {
+        // enemy pieces giving check
+        pub checkers: u64,
+        // own pieces that can't leave the line between their king and an enemy slider
+        pub pinned: u64,
+        // squares a non-king move has to land on, every square when not in check
+        pub check_mask: u64,
+    }
+
+    // How the game stands. ChessBoard::status only judges the board itself,
+    // the repetition and seventy-five move variants come from Game::status.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum GameStatus {
+        Ongoing,
+        Checkmate { winner: Color },
+        Stalemate,
+        InsufficientMaterial,
+        // draws a player can claim
+        FiftyMoveRule,
+        ThreefoldRepetition,
+        // draws that end the game by themselves
+        SeventyFiveMoveRule,
+        FivefoldRepetition,
+    }
+
+    impl GameStatus {
+        pub fn is_over(&self) -> bool {
+            return *self != GameStatus::Ongoing;
+        }
+
+        // result as written in PGN
+        pub fn result(&self) -> &'static str {
+            match self {
+                GameStatus::Ongoing => "*",
+                GameStatus::Checkmate { winner: Color::White } => "1-0",
+                GameStatus::Checkmate { winner: Color::Black } => "0-1",
+                _ => "1/2-1/2",
+            }
+        }
+    }
+
+    // Score of a position where the side to move gets mated, faster mates score higher
+    pub const MATE_SCORE: f32 = 10000.0;
+
+    // equal boards always share a key, so hashing the key alone is enough
+    impl Hash for ChessBoard {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u64(self.zobrist_key);
+        }
+    }
+
+    impl Default for ChessBoard {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl ChessBoard {
         pub fn new() -> Self {
-            Self { 
+            let mut chessboard = Self {
                 white_to_move: true,
-                prev_pos_pawns: 0,
+                en_passant_square: None,
+
+                castling_rights: CastlingRights::ALL,
 
-                white_moved_king: false,
-                white_moved_A_rook: false,
-                white_moved_H_rook: false,
                 white_pawns: 0xFF00,
                 white_rooks: 0x81,
                 white_knights: 0x42,
                 white_bishops: 0x24,
                 white_queens: 0x8,
                 white_king: 0x10,
-            
-                black_moved_king: false,
-                black_moved_A_rook: false,
-                black_moved_H_rook: false,
+
                 black_pawns: 0xFF000000000000,
                 black_rooks: 0x8100000000000000,
                 black_knights: 0x4200000000000000,
                 black_bishops: 0x2400000000000000,
                 black_queens: 0x800000000000000,
                 black_king: 0x1000000000000000,
+
+                halfmove_clock: 0,
+                fullmove_number: 1,
+
+                zobrist_key: 0,
+            };
+            chessboard.zobrist_key = chessboard.compute_zobrist_key();
+            return chessboard;
+        }
+
+        // Full hash of the position, make_move updates it incrementally instead
+        pub fn compute_zobrist_key(&self) -> u64 {
+            let mut key: u64 = 0;
+            for piece in [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King] {
+                for (white, mut bitboard) in [(true, self.get_white_bitboard(piece)), (false, self.get_black_bitboard(piece))] {
+                    while bitboard > 0 {
+                        let square = bitboard & bitboard.wrapping_neg();
+                        key ^= ZOBRIST_KEYS.piece(white, piece, square);
+                        bitboard -= square;
+                    }
+                }
+            }
+
+            if !self.white_to_move {
+                key ^= ZOBRIST_KEYS.black_to_move;
             }
+            key ^= ZOBRIST_KEYS.castling_rights[self.castling_rights as usize];
+            key ^= self.en_passant_zobrist_key();
+            return key;
+        }
+
+        // The en passant file only changes the position when a pawn of the side to
+        // move could actually capture there, otherwise a position reached by a
+        // double push has to repeat the same position reached without one
+        fn en_passant_zobrist_key(&self) -> u64 {
+            let Some(square) = self.en_passant_square else { return 0 };
+            let (own_pawns, enemy) = if self.white_to_move { (self.white_pawns, Color::Black) } else { (self.black_pawns, Color::White) };
+            // the own pawns that attack square are the ones an enemy pawn on square would attack
+            if PAWN_ATTACKS[enemy as usize][square as usize] & own_pawns == 0 {
+                return 0;
+            }
+            return ZOBRIST_KEYS.en_passant(self.en_passant_square);
         }
 
         pub fn get_all_white_pieces (&self) -> u64 {
@@ -119,1266 +273,619 @@ pub mod chessboard {
             self.black_pawns | self.black_rooks | self.black_knights | self.black_bishops | self.black_queens | self.black_king
         }
 
-        pub fn remove_white_piece(&mut self, square: u64) {
-            // if self.get_all_white_pieces() & square == 0 { println!("GRESKA 1 !!!"); return; }; // TODO: remove
-
-            if self.white_pawns & square > 0 { self.white_pawns -= square; return; }
-            if self.white_rooks & square > 0 { self.white_rooks -= square; return; }
-            if self.white_knights & square > 0 { self.white_knights -= square; return; }
-            if self.white_bishops & square > 0 { self.white_bishops -= square; return; }
-            if self.white_queens & square > 0 { self.white_queens -= square; return; }
-            if self.white_king & square > 0 { self.white_king -= square; return; }
+        pub fn get_white_piece(&self, square: u64) -> Option<Piece> {
+            if self.white_pawns & square > 0 { return Some(Piece::Pawn); }
+            if self.white_rooks & square > 0 { return Some(Piece::Rook); }
+            if self.white_knights & square > 0 { return Some(Piece::Knight); }
+            if self.white_bishops & square > 0 { return Some(Piece::Bishop); }
+            if self.white_queens & square > 0 { return Some(Piece::Queen); }
+            if self.white_king & square > 0 { return Some(Piece::King); }
+            return None;
         }
 
-        pub fn remove_black_piece(&mut self, square: u64) {
-            // if self.get_all_black_pieces() & square == 0 { println!("GRESKA 2 !!!"); return; }; // TODO: remove
-
-            if self.black_pawns & square > 0 { self.black_pawns -= square; return; }
-            if self.black_rooks & square > 0 { self.black_rooks -= square; return; }
-            if self.black_knights & square > 0 { self.black_knights -= square; return; }
-            if self.black_bishops & square > 0 { self.black_bishops -= square; return; }
-            if self.black_queens & square > 0 { self.black_queens -= square; return; }
-            if self.black_king & square > 0 { self.black_king -= square; return; }
+        pub fn get_black_piece(&self, square: u64) -> Option<Piece> {
+            if self.black_pawns & square > 0 { return Some(Piece::Pawn); }
+            if self.black_rooks & square > 0 { return Some(Piece::Rook); }
+            if self.black_knights & square > 0 { return Some(Piece::Knight); }
+            if self.black_bishops & square > 0 { return Some(Piece::Bishop); }
+            if self.black_queens & square > 0 { return Some(Piece::Queen); }
+            if self.black_king & square > 0 { return Some(Piece::King); }
+            return None;
         }
 
-        pub fn get_all_pieces(&self) -> u64 {
-            self.get_all_white_pieces() | self.get_all_black_pieces()
+        pub fn get_white_bitboard(&self, piece: Piece) -> u64 {
+            match piece {
+                Piece::Pawn => self.white_pawns,
+                Piece::Knight => self.white_knights,
+                Piece::Bishop => self.white_bishops,
+                Piece::Rook => self.white_rooks,
+                Piece::Queen => self.white_queens,
+                Piece::King => self.white_king,
+            }
         }
 
-        pub fn print_chessboard(&self) {
-            println!("---------------\n");
-            let mut square;
-            for i in 0..8 {
-                for j in 56 - i * 8..64 - i * 8 {
-                    square = 2_u64.pow(j);
-                    if self.black_pawns & square > 0 { print!("\u{265F} "); continue; }
-                    if self.black_rooks & square > 0 { print!("\u{265C} "); continue; }
-                    if self.black_knights & square > 0 { print!("\u{265E} "); continue; }
-                    if self.black_bishops & square > 0 { print!("\u{265D} "); continue; }
-                    if self.black_queens & square > 0 { print!("\u{265B} "); continue; }
-                    if self.black_king & square > 0 { print!("\u{265A} " ); continue; }
+        pub fn get_black_bitboard(&self, piece: Piece) -> u64 {
+            match piece {
+                Piece::Pawn => self.black_pawns,
+                Piece::Knight => self.black_knights,
+                Piece::Bishop => self.black_bishops,
+                Piece::Rook => self.black_rooks,
+                Piece::Queen => self.black_queens,
+                Piece::King => self.black_king,
+            }
+        }
 
-                    if self.white_pawns & square > 0 { print!("\u{2659} "); continue; }
-                    if self.white_rooks & square > 0 { print!("\u{2656} "); continue; }
-                    if self.white_knights & square > 0 { print!("\u{2658} "); continue; }
-                    if self.white_bishops & square > 0 { print!("\u{2657} "); continue; }
-                    if self.white_queens & square > 0 { print!("\u{2655} "); continue; }
-                    if self.white_king & square > 0 { print!("\u{2654} "); continue; }
-                    print!("  ");
-                }
-                print!("\n");
+        pub fn white_bitboard_mut(&mut self, piece: Piece) -> &mut u64 {
+            match piece {
+                Piece::Pawn => &mut self.white_pawns,
+                Piece::Knight => &mut self.white_knights,
+                Piece::Bishop => &mut self.white_bishops,
+                Piece::Rook => &mut self.white_rooks,
+                Piece::Queen => &mut self.white_queens,
+                Piece::King => &mut self.white_king,
             }
-            print!("\n");
         }
 
-        pub fn is_white_king_checked(&self) -> (bool, Vec<ChessBoard>) {
-            let mut next_pseudo_legal_black_moves = self.get_all_pseudo_legal_black_moves();
-            let starting_len = next_pseudo_legal_black_moves.len();
+        pub fn black_bitboard_mut(&mut self, piece: Piece) -> &mut u64 {
+            match piece {
+                Piece::Pawn => &mut self.black_pawns,
+                Piece::Knight => &mut self.black_knights,
+                Piece::Bishop => &mut self.black_bishops,
+                Piece::Rook => &mut self.black_rooks,
+                Piece::Queen => &mut self.black_queens,
+                Piece::King => &mut self.black_king,
+            }
+        }
 
-            next_pseudo_legal_black_moves.retain(|&e| e.white_king > 0);
-            let curr_len = next_pseudo_legal_black_moves.len();
+        pub fn remove_white_piece(&mut self, square: u64) -> Option<Piece> {
+            let piece = self.get_white_piece(square)?;
+            *self.white_bitboard_mut(piece) -= square;
+            return Some(piece);
+        }
 
-            let is_king_checked = starting_len != curr_len;
+        pub fn remove_black_piece(&mut self, square: u64) -> Option<Piece> {
+            let piece = self.get_black_piece(square)?;
+            *self.black_bitboard_mut(piece) -= square;
+            return Some(piece);
+        }
 
-            return (is_king_checked, next_pseudo_legal_black_moves);
+        pub fn get_all_pieces(&self) -> u64 {
+            self.get_all_white_pieces() | self.get_all_black_pieces()
         }
 
-        pub fn is_black_king_checked(&self) -> (bool, Vec<ChessBoard>) {
-            let mut next_pseudo_legal_white_moves = self.get_all_pseudo_legal_white_moves();
-            let starting_len = next_pseudo_legal_white_moves.len();
+        // Squares claimed by more than one piece bitboard, 0 for a consistent board
+        pub fn get_overlapping_squares(&self) -> u64 {
+            let bitboards = [
+                self.white_pawns, self.white_rooks, self.white_knights, self.white_bishops, self.white_queens, self.white_king,
+                self.black_pawns, self.black_rooks, self.black_knights, self.black_bishops, self.black_queens, self.black_king,
+            ];
+
+            let mut occupied: u64 = 0;
+            let mut overlapping: u64 = 0;
+            for bitboard in bitboards {
+                overlapping |= occupied & bitboard;
+                occupied |= bitboard;
+            }
+            return overlapping;
+        }
 
-            next_pseudo_legal_white_moves.retain(|&e| e.black_king > 0);
-            let curr_len = next_pseudo_legal_white_moves.len();
+        pub fn assert_consistent(&self) {
+            let overlapping = self.get_overlapping_squares();
+            if overlapping > 0 {
+                self.print_chessboard();
+                panic!("squares occupied by two pieces: {:#018x}", overlapping);
+            }
+        }
 
-            let is_king_checked = starting_len != curr_len;
+        pub fn make_move(&mut self, mov: Move) -> UndoInfo {
+            let mut undo = UndoInfo {
+                captured: None,
+                en_passant_square: self.en_passant_square,
+                castling_rights: self.castling_rights,
+                halfmove_clock: self.halfmove_clock,
+                zobrist_key: self.zobrist_key,
+            };
+            let (from, to) = (mov.get_from_square(), mov.get_to_square());
+            self.zobrist_key ^= self.en_passant_zobrist_key();
+            self.zobrist_key ^= ZOBRIST_KEYS.castling_rights[self.castling_rights as usize];
+            self.en_passant_square = None;
+
+            // pawn moves and captures reset the fifty move counter
+            if (self.white_pawns | self.black_pawns) & from > 0 || mov.is_capture() {
+                self.halfmove_clock = 0;
+            } else {
+                self.halfmove_clock += 1;
+            }
+            if !self.white_to_move {
+                self.fullmove_number += 1;
+            }
 
-            return (is_king_checked, next_pseudo_legal_white_moves);
-        }
-        
-        pub fn get_all_pseudo_legal_white_pawn_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let white_pawns = self.white_pawns;
-            let mut square: u64;
-            // 64 - 8 = 56 // cant occupy 1st rank
-            for i in 8..56 {
-                square = 1 << i;
-                // square =  2_u64.pow(i);
-                // check if pawn occupies square
-                if white_pawns & square > 0 {
+            if self.white_to_move {
+                let piece = self.get_white_piece(from).expect("no white piece on move's from square");
 
-                    // CHECK FOR UPRIGHT TAKE ( attacked_square = square << 9 )
-                    // let attacked_square = square * 2_u64.pow(9);
-                    let (attacked_square, overflow) = square.overflowing_loss_checked_shl(9);
-                    // cant be on A file after taking upright
-                    if !overflow && attacked_square & Constants::A_FILE == 0 {
-                        //check if there is enemy piece and take
-                        check_white_pawn_take(square, attacked_square, self, &mut result);
+                if mov.is_en_passant() {
+                    self.black_pawns -= to >> 8;
+                    undo.captured = Some(Piece::Pawn);
+                    self.zobrist_key ^= ZOBRIST_KEYS.piece(false, Piece::Pawn, to >> 8);
+                } else if mov.is_capture() {
+                    undo.captured = self.remove_black_piece(to);
+                    if let Some(captured) = undo.captured {
+                        self.zobrist_key ^= ZOBRIST_KEYS.piece(false, captured, to);
                     }
+                }
 
-                    // CHECK FOR UPLEFT TAKE ( attacked_square = square << 7 )
-                    let attacked_square = square << 7;
-                    // cant be on H file after taking upleft
-                    if attacked_square & Constants::H_FILE == 0 {
-                        //check if there is enemy piece and take
-                        check_white_pawn_take(square, attacked_square, self, &mut result);
-                    }
+                *self.white_bitboard_mut(piece) -= from;
+                *self.white_bitboard_mut(mov.promotion.unwrap_or(piece)) += to;
+                self.zobrist_key ^= ZOBRIST_KEYS.piece(true, piece, from) ^ ZOBRIST_KEYS.piece(true, mov.promotion.unwrap_or(piece), to);
 
-                    // CHECK FOR 1 SQUARE FORWARD
-                    // let forward_square_1 = square * 2_u64.pow(8);
-                    let forward_square_1 = square << 8;
+                // DOUBLE PAWN PUSH
+                if piece == Piece::Pawn && to == from << 16 {
+                    self.en_passant_square = Some(mov.from + 8);
+                }
 
-                    if (forward_square_1 & self.get_all_pieces()) == 0 {
-                        white_pawn_forward(square, forward_square_1, self, &mut result);
+                if mov.is_castle() {
+                    let (a1, d1, f1, h1) = (1, 8, 32, 128);
+                    // SHORT CASTLE
+                    if to > from {
+                        self.white_rooks -= h1;
+                        self.white_rooks += f1;
+                        self.zobrist_key ^= ZOBRIST_KEYS.piece(true, Piece::Rook, h1) ^ ZOBRIST_KEYS.piece(true, Piece::Rook, f1);
+                    } else {
+                        self.white_rooks -= a1;
+                        self.white_rooks += d1;
+                        self.zobrist_key ^= ZOBRIST_KEYS.piece(true, Piece::Rook, a1) ^ ZOBRIST_KEYS.piece(true, Piece::Rook, d1);
                     }
+                }
+            } else {
+                let piece = self.get_black_piece(from).expect("no black piece on move's from square");
 
-                    // CHECK FOR 2 SQUARES FORWARD
-                    let forward_square_2 = square << 16;
-
-                    if square & Constants::SECOND_RANK > 0 && forward_square_1 & self.get_all_pieces() == 0 && forward_square_2 & self.get_all_pieces() == 0 {
-                        white_pawn_forward(square, forward_square_2, self, &mut result)
+                if mov.is_en_passant() {
+                    self.white_pawns -= to << 8;
+                    undo.captured = Some(Piece::Pawn);
+                    self.zobrist_key ^= ZOBRIST_KEYS.piece(true, Piece::Pawn, to << 8);
+                } else if mov.is_capture() {
+                    undo.captured = self.remove_white_piece(to);
+                    if let Some(captured) = undo.captured {
+                        self.zobrist_key ^= ZOBRIST_KEYS.piece(true, captured, to);
                     }
+                }
 
-                    // CHECK FOR EN PASSANT
-                    if square & Constants::FIFTH_RANK > 0 {
-                        // CHECK LEFT EN PASSANT
-                        if (square >> 1) & self.black_pawns > 0 && self.prev_pos_pawns & ( square >> 1 << 16 ) > 0 {
-                            white_en_passant_move(square, square >> 1, self, &mut result)
-                        }
+                *self.black_bitboard_mut(piece) -= from;
+                *self.black_bitboard_mut(mov.promotion.unwrap_or(piece)) += to;
+                self.zobrist_key ^= ZOBRIST_KEYS.piece(false, piece, from) ^ ZOBRIST_KEYS.piece(false, mov.promotion.unwrap_or(piece), to);
 
+                // DOUBLE PAWN PUSH
+                if piece == Piece::Pawn && to == from >> 16 {
+                    self.en_passant_square = Some(mov.from - 8);
+                }
 
-                        // CHECK RIGHT EN PASSANT
-                        if (square << 1) & self.black_pawns > 0 && self.prev_pos_pawns & ( square << 1 << 16 ) > 0 {
-                            white_en_passant_move(square, square << 1, self, &mut result)
-                        }
+                if mov.is_castle() {
+                    let (a8, d8, f8, h8) = (0x100000000000000, 0x800000000000000, 0x2000000000000000, 0x8000000000000000);
+                    // SHORT CASTLE
+                    if to > from {
+                        self.black_rooks -= h8;
+                        self.black_rooks += f8;
+                        self.zobrist_key ^= ZOBRIST_KEYS.piece(false, Piece::Rook, h8) ^ ZOBRIST_KEYS.piece(false, Piece::Rook, f8);
+                    } else {
+                        self.black_rooks -= a8;
+                        self.black_rooks += d8;
+                        self.zobrist_key ^= ZOBRIST_KEYS.piece(false, Piece::Rook, a8) ^ ZOBRIST_KEYS.piece(false, Piece::Rook, d8);
                     }
                 }
-            };
-
-            return result;
-        }
+            }
 
-        pub fn get_all_pseudo_legal_black_pawn_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let black_pawns = self.black_pawns;
-            let mut square: u64;
-            // 64 - 8 = 56 // cant occupy 1st rank
-            for i in 0..56 {
-                square =  1 << i;
-                // check if pawn occupies square
-                if black_pawns & square > 0 {
-
-                    // CHECK FOR DOWNRIGHT TAKE
-                    let attacked_square = square >> 7;
-                    // cant be on A file after taking DOWNRIGHT
-                    if attacked_square & Constants::A_FILE == 0 {
-                        //check if there is enemy piece
-                        if attacked_square & self.get_all_white_pieces() > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_pawns -= square;
-                            new_chessboard.black_pawns += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        }
-                    }
+            // moving the king or a rook, or capturing a rook, gives up castling on that side
+            self.castling_rights &= !(CastlingRights::revoked_by(from) | CastlingRights::revoked_by(to));
 
-                    // CHECK FOR DOWNLEFT TAKE
-                    let attacked_square = square >> 9;
-                    // cant be on H file after taking DOWNLEFT
-                    if attacked_square > 0 && attacked_square & Constants::H_FILE == 0 {
-                        //check if there is enemy piece
-                        if attacked_square & self.get_all_white_pieces() > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_pawns -= square;
-                            new_chessboard.black_pawns += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        }
-                    }
+            self.white_to_move = !self.white_to_move;
+            self.zobrist_key ^= ZOBRIST_KEYS.castling_rights[self.castling_rights as usize];
+            self.zobrist_key ^= self.en_passant_zobrist_key();
+            self.zobrist_key ^= ZOBRIST_KEYS.black_to_move;
 
-                    // CHECK FOR 1 SQUARE FORWARD
-                    let forward_square_1 = square >> 8;
-
-                    if (forward_square_1 & self.get_all_pieces()) == 0 {
-                        // PUSH PAWN IF NOT ON EIGHT RANK
-                        if (forward_square_1 & Constants::FIRST_RANK) == 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_pawns -= square;
-                            new_chessboard.black_pawns += forward_square_1;
-                            result.push(new_chessboard);
-                        }
-                        // TODO: PROMOTE TO QUEEN 
-                        else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_pawns -= square;
-                            new_chessboard.black_queens += forward_square_1;
-                            result.push(new_chessboard);
-                        }
+            // catch movegen bugs as soon as they corrupt the board
+            #[cfg(debug_assertions)]
+            {
+                self.assert_consistent();
+                debug_assert_eq!(self.zobrist_key, self.compute_zobrist_key(), "incremental zobrist key diverged");
+            }
 
+            return undo;
+        }
 
+        pub fn unmake_move(&mut self, mov: Move, undo: UndoInfo) {
+            let (from, to) = (mov.get_from_square(), mov.get_to_square());
+            self.white_to_move = !self.white_to_move;
 
-                    }
+            if self.white_to_move {
+                let moved_piece = mov.promotion.unwrap_or_else(|| self.get_white_piece(to).expect("no white piece on move's to square"));
+                *self.white_bitboard_mut(moved_piece) -= to;
+                *self.white_bitboard_mut(if mov.is_promotion() { Piece::Pawn } else { moved_piece }) += from;
+
+                if mov.is_en_passant() {
+                    self.black_pawns += to >> 8;
+                } else if let Some(captured) = undo.captured {
+                    *self.black_bitboard_mut(captured) += to;
+                }
 
-                    // CHECK FOR 2 SQUARES FORWARD
-                    let forward_square_2 = square >> 16;
-
-                    if square & Constants::SEVENTH_RANK > 0 && forward_square_1 & self.get_all_pieces() == 0 && forward_square_2 & self.get_all_pieces() == 0 {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                        
-                        new_chessboard.black_pawns -= square;
-                        new_chessboard.black_pawns += forward_square_2;
-                        result.push(new_chessboard);
+                if mov.is_castle() {
+                    let (a1, d1, f1, h1) = (1, 8, 32, 128);
+                    if to > from {
+                        self.white_rooks -= f1;
+                        self.white_rooks += h1;
+                    } else {
+                        self.white_rooks -= d1;
+                        self.white_rooks += a1;
                     }
+                }
+            } else {
+                let moved_piece = mov.promotion.unwrap_or_else(|| self.get_black_piece(to).expect("no black piece on move's to square"));
+                *self.black_bitboard_mut(moved_piece) -= to;
+                *self.black_bitboard_mut(if mov.is_promotion() { Piece::Pawn } else { moved_piece }) += from;
+
+                if mov.is_en_passant() {
+                    self.white_pawns += to << 8;
+                } else if let Some(captured) = undo.captured {
+                    *self.white_bitboard_mut(captured) += to;
+                }
 
-                    // CHECK FOR EN PASSANT
-                    if square & Constants::FOURTH_RANK > 0 {
-                        // CHECK LEFT EN PASSANT
-                        if (square >> 1) & self.white_pawns > 0 && self.prev_pos_pawns & ( square >> 1 >> 16 ) > 0 {
-                            black_en_passant_move(square, square >> 1, self, &mut result)
-                        }
-
-
-                        // CHECK RIGHT EN PASSANT
-                        if (square << 1) & self.white_pawns > 0 && self.prev_pos_pawns & ( square << 1 >> 16 ) > 0 {
-                            black_en_passant_move(square, square << 1, self, &mut result)
-                        }
+                if mov.is_castle() {
+                    let (a8, d8, f8, h8) = (0x100000000000000, 0x800000000000000, 0x2000000000000000, 0x8000000000000000);
+                    if to > from {
+                        self.black_rooks -= f8;
+                        self.black_rooks += h8;
+                    } else {
+                        self.black_rooks -= d8;
+                        self.black_rooks += a8;
                     }
                 }
-            };
+            }
 
-            return result;
+            self.en_passant_square = undo.en_passant_square;
+            self.castling_rights = undo.castling_rights;
+            self.halfmove_clock = undo.halfmove_clock;
+            self.zobrist_key = undo.zobrist_key;
+            if !self.white_to_move {
+                self.fullmove_number -= 1;
+            }
         }
-    
-        pub fn get_all_pseudo_legal_white_rook_moves(&self) -> Vec<ChessBoard> {
-            
-            let mut result: Vec<ChessBoard> = vec![];
-            let white_rooks = self.white_rooks;
-            let mut square: u64;
-            for i in 0..64 {
-                square =  1 << i;
-                // check if rook occupies square
-                if white_rooks & square > 0 {
-
-                    // CHECK UP
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(8);
-                    while !overflow && (self.get_all_pieces() & attacked_square) == 0 {
-                        
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(8);
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_black_pieces() & attacked_square > 0 && !overflow{
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        new_chessboard.remove_black_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
 
-                    // CHECK DOWN
-                    attacked_square = square >> 8;
-                    while attacked_square > 0 && (self.get_all_pieces() & attacked_square) == 0 {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        attacked_square = attacked_square >> 8;
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_black_pieces() & attacked_square > 0 {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        new_chessboard.remove_black_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
+        // 50 moves by each side without a pawn move or a capture
+        pub fn is_fifty_move_draw(&self) -> bool {
+            self.halfmove_clock >= 100
+        }
 
-                    // CHECK LEFT
-                    attacked_square = square >> 1;
-                    while (attacked_square & Constants::H_FILE) == 0 && (self.get_all_pieces() & attacked_square) == 0 && attacked_square > 0 {
-                        
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        attacked_square = attacked_square >> 1;
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_black_pieces() & attacked_square > 0 && ( attacked_square > 0 && (attacked_square & Constants::H_FILE) == 0 ){
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        new_chessboard.remove_black_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
+        pub fn print_chessboard(&self) {
+            println!("---------------\n");
+            let mut square;
+            for i in 0..8 {
+                for j in 56 - i * 8..64 - i * 8 {
+                    square = 2_u64.pow(j);
+                    if self.black_pawns & square > 0 { print!("\u{265F} "); continue; }
+                    if self.black_rooks & square > 0 { print!("\u{265C} "); continue; }
+                    if self.black_knights & square > 0 { print!("\u{265E} "); continue; }
+                    if self.black_bishops & square > 0 { print!("\u{265D} "); continue; }
+                    if self.black_queens & square > 0 { print!("\u{265B} "); continue; }
+                    if self.black_king & square > 0 { print!("\u{265A} " ); continue; }
 
-                    // CHECK RIGHT
-                    (attacked_square, overflow) = square.overflowing_loss_checked_shl(1);
-                    while (attacked_square & Constants::A_FILE) == 0 && (self.get_all_pieces() & attacked_square) == 0 && !overflow {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(1);
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_black_pieces() & attacked_square > 0 && ( (attacked_square & Constants::A_FILE) == 0 && !overflow ){
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                    
-                        new_chessboard.white_rooks -= square;
-                        new_chessboard.white_rooks += attacked_square;
-                        new_chessboard.remove_black_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
+                    if self.white_pawns & square > 0 { print!("\u{2659} "); continue; }
+                    if self.white_rooks & square > 0 { print!("\u{2656} "); continue; }
+                    if self.white_knights & square > 0 { print!("\u{2658} "); continue; }
+                    if self.white_bishops & square > 0 { print!("\u{2657} "); continue; }
+                    if self.white_queens & square > 0 { print!("\u{2655} "); continue; }
+                    if self.white_king & square > 0 { print!("\u{2654} "); continue; }
+                    print!("  ");
                 }
+                println!();
             }
-            return result;
+            println!();
         }
-    
-        pub fn get_all_pseudo_legal_black_rook_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let black_rooks = self.black_rooks;
-            let mut square: u64;
-            for i in 0..64 {
-                square =  1 << i;
-                // check if rook occupies square
-                if black_rooks & square > 0 {
-
-                    // CHECK UP
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(8);
-                    while !overflow && (self.get_all_pieces() & attacked_square) == 0{
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(8);
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_white_pieces() & attacked_square > 0 && !overflow{
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        new_chessboard.remove_white_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
 
-                    // CHECK DOWN
-                    attacked_square = square >> 8;
-                    while attacked_square > 0 && (self.get_all_pieces() & attacked_square) == 0 {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        attacked_square = attacked_square >> 8;
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_white_pieces() & attacked_square > 0 {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        new_chessboard.remove_white_piece(attacked_square);
-                        // new_chessboard.print_chessboard();
-                        // println!("Evaluation: {}", new_chessboard.evaluate());
-                        result.push(new_chessboard);
-                    }
+        // Every piece of either color attacking square
+        pub fn attackers_to(&self, square: u64) -> u64 {
+            let i = square.trailing_zeros() as usize;
+            let occupancy = self.get_all_pieces();
+            let bishops_queens = self.white_bishops | self.white_queens | self.black_bishops | self.black_queens;
+            let rooks_queens = self.white_rooks | self.white_queens | self.black_rooks | self.black_queens;
+
+            // a pawn attacks square iff a pawn of the other color on square would attack it
+            return (PAWN_ATTACKS[Color::Black as usize][i] & self.white_pawns)
+                | (PAWN_ATTACKS[Color::White as usize][i] & self.black_pawns)
+                | (KNIGHT_ATTACKS[i] & (self.white_knights | self.black_knights))
+                | (KING_ATTACKS[i] & (self.white_king | self.black_king))
+                | (bishop_attacks(i, occupancy) & bishops_queens)
+                | (rook_attacks(i, occupancy) & rooks_queens);
+        }
 
-                    // CHECK LEFT
-                    attacked_square = square >> 1;
-                    while (attacked_square & Constants::H_FILE) == 0 && (self.get_all_pieces() & attacked_square) == 0 && attacked_square > 0 {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        attacked_square = attacked_square >> 1;
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_white_pieces() & attacked_square > 0 && ( (attacked_square & Constants::H_FILE) == 0 && attacked_square > 0 ) {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        new_chessboard.remove_white_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
+        pub fn is_square_attacked(&self, square: u64, by_color: Color) -> bool {
+            return self.is_square_attacked_with_occupancy(square, by_color, self.get_all_pieces());
+        }
 
-                    // CHECK RIGHT
-                    (attacked_square, overflow) = square.overflowing_loss_checked_shl(1);
-                    while (attacked_square & Constants::A_FILE) == 0 && (self.get_all_pieces() & attacked_square) == 0 && !overflow {
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        result.push(new_chessboard);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(1);
-                        
-                    }
-                    //check if while exited because of enemy piece
-                    if self.get_all_white_pieces() & attacked_square > 0 && ( (attacked_square & Constants::A_FILE) == 0 && !overflow ){
-                        let mut new_chessboard = self.clone();
-                        new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                        new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                    
-                        new_chessboard.black_rooks -= square;
-                        new_chessboard.black_rooks += attacked_square;
-                        new_chessboard.remove_white_piece(attacked_square);
-                        result.push(new_chessboard);
-                    }
-                }
-            }
-            return result;
+        // sliders see through every square missing from occupancy
+        fn is_square_attacked_with_occupancy(&self, square: u64, by_color: Color, occupancy: u64) -> bool {
+            let i = square.trailing_zeros() as usize;
+            let (pawns, knights, bishops, rooks, queens, king) = match by_color {
+                Color::White => (self.white_pawns, self.white_knights, self.white_bishops, self.white_rooks, self.white_queens, self.white_king),
+                Color::Black => (self.black_pawns, self.black_knights, self.black_bishops, self.black_rooks, self.black_queens, self.black_king),
+            };
+            let defender = match by_color {
+                Color::White => Color::Black,
+                Color::Black => Color::White,
+            };
+
+            // cheapest lookups first
+            return PAWN_ATTACKS[defender as usize][i] & pawns > 0
+                || KNIGHT_ATTACKS[i] & knights > 0
+                || KING_ATTACKS[i] & king > 0
+                || bishop_attacks(i, occupancy) & (bishops | queens) > 0
+                || rook_attacks(i, occupancy) & (rooks | queens) > 0;
         }
-    
-        pub fn get_all_pseudo_legal_white_knight_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let white_knights = self.white_knights;
-            let mut square: u64;
-            for i in 0..64 {
-                square =  1 << i;
-                // check if knight occupies square
-                if white_knights & square > 0 {
-                    let (mut attacked_square, mut overflow ) = square.overflowing_loss_checked_shl(17);
-
-                    // UP-RIGHT
-                    if !overflow && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0 {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
 
-                    // UP-LEFT
-                    (attacked_square, overflow)  = square.overflowing_loss_checked_shl(15);
-
-                    if !overflow && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0 {
-                        
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+        pub fn is_white_king_checked(&self) -> bool {
+            return self.is_square_attacked(self.white_king, Color::Black);
+        }
 
-                    // DOWN-RIGHT
-                    attacked_square  = square >> 15;
-                    if (attacked_square > 0) && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0 {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+        pub fn is_black_king_checked(&self) -> bool {
+            return self.is_square_attacked(self.black_king, Color::White);
+        }
 
-                    // DOWN-LEFT
-                    attacked_square  = square >> 17;
-                    if (attacked_square > 0) && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0 {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+        pub fn get_all_pseudo_legal_white_pawn_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut white_pawns = self.white_pawns;
+            let en_passant_square: u64 = self.en_passant_square.map_or(0, |square| 1 << square);
+            while white_pawns > 0 {
+                let i = white_pawns.trailing_zeros() as usize;
+                let square: u64 = 1 << i;
+
+                // CHECK FOR TAKES
+                let mut attacked_squares = PAWN_ATTACKS[Color::White as usize][i] & self.get_all_black_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    check_white_pawn_take(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
+                }
 
-                    // RIGHT-UP --^
-                    (attacked_square, overflow)  = square.overflowing_loss_checked_shl(10);
-                    if !overflow && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & Constants::B_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0 {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                // CHECK FOR 1 SQUARE FORWARD
+                // let forward_square_1 = square * 2_u64.pow(8);
+                let forward_square_1 = square << 8;
 
-                    // RIGHT-DOWN
-                    attacked_square  = square >> 6;
-                    if attacked_square > 0 && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & Constants::B_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0 {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                if (forward_square_1 & self.get_all_pieces()) == 0 {
+                    white_pawn_forward(square, forward_square_1, &mut result);
+                }
 
-                    // LEFT-UP ^--
-                    (attacked_square, overflow)  = square.overflowing_loss_checked_shl(6);
-                    if !overflow && (attacked_square & Constants::G_FILE) == 0 && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0  {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                // CHECK FOR 2 SQUARES FORWARD
+                let forward_square_2 = square << 16;
 
-                    // LEFT-DOWN
-                    attacked_square  = square >> 10;
-                    if attacked_square > 0 && (attacked_square & Constants::G_FILE) == 0 && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_white_pieces()) == 0  {
-                        if (self.get_all_black_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            new_chessboard.remove_black_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.white_pawns;
-                            
-                            new_chessboard.white_knights -= square;
-                            new_chessboard.white_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                if square & Constants::SECOND_RANK > 0 && forward_square_1 & self.get_all_pieces() == 0 && forward_square_2 & self.get_all_pieces() == 0 {
+                    white_pawn_forward(square, forward_square_2, &mut result)
+                }
 
+                // CHECK FOR EN PASSANT
+                if PAWN_ATTACKS[Color::White as usize][i] & en_passant_square > 0 {
+                    white_en_passant_move(square, en_passant_square, &mut result)
                 }
+                white_pawns -= square;
             }
+
             return result;
         }
-    
-        pub fn get_all_pseudo_legal_black_knight_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let black_knights = self.black_knights;
-            let mut square: u64;
-            for i in 0..64 {
-                square =  1 << i;
-                // check if knight occupies square
-                if black_knights & square > 0 {
-                    let (mut attacked_square, mut overflow ) = square.overflowing_loss_checked_shl(17);
-
-                    // UP-RIGHT
-                    if !overflow && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0 {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
-
-                    // UP-LEFT
-                    (attacked_square, overflow)  = square.overflowing_loss_checked_shl(15);
-
-                    if !overflow && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0 {
-                        
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
 
-                    // DOWN-RIGHT
-                    attacked_square  = square >> 15;
-                    if (attacked_square > 0) && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0 {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
-
-                    // DOWN-LEFT
-                    attacked_square  = square >> 17;
-                    if (attacked_square > 0) && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0 {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+        pub fn get_all_pseudo_legal_black_pawn_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut black_pawns = self.black_pawns;
+            let en_passant_square: u64 = self.en_passant_square.map_or(0, |square| 1 << square);
+            while black_pawns > 0 {
+                let i = black_pawns.trailing_zeros() as usize;
+                let square: u64 = 1 << i;
+
+                // CHECK FOR TAKES
+                let mut attacked_squares = PAWN_ATTACKS[Color::Black as usize][i] & self.get_all_white_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    check_black_pawn_take(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
+                }
 
-                    // RIGHT-UP --^
-                    (attacked_square, overflow)  = square.overflowing_loss_checked_shl(10);
-                    if !overflow && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & Constants::B_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0 {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                // CHECK FOR 1 SQUARE FORWARD
+                let forward_square_1 = square >> 8;
 
-                    // RIGHT-DOWN
-                    attacked_square  = square >> 6;
-                    if attacked_square > 0 && (attacked_square & Constants::A_FILE) == 0 && (attacked_square & Constants::B_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0 {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                if (forward_square_1 & self.get_all_pieces()) == 0 {
+                    black_pawn_forward(square, forward_square_1, &mut result);
+                }
 
-                    // LEFT-UP ^--
-                    (attacked_square, overflow)  = square.overflowing_loss_checked_shl(6);
-                    if !overflow && (attacked_square & Constants::G_FILE) == 0 && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0  {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                // CHECK FOR 2 SQUARES FORWARD
+                let forward_square_2 = square >> 16;
 
-                    // LEFT-DOWN
-                    attacked_square  = square >> 10;
-                    if attacked_square > 0 && (attacked_square & Constants::G_FILE) == 0 && (attacked_square & Constants::H_FILE) == 0 && (attacked_square & self.get_all_black_pieces()) == 0  {
-                        if (self.get_all_white_pieces() & attacked_square) > 0 {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            new_chessboard.remove_white_piece(attacked_square);
-                            result.push(new_chessboard);
-                        } else {
-                            let mut new_chessboard = self.clone();
-                            new_chessboard.white_to_move = !new_chessboard.white_to_move;
-                            new_chessboard.prev_pos_pawns = new_chessboard.black_pawns;
-                            
-                            new_chessboard.black_knights -= square;
-                            new_chessboard.black_knights += attacked_square;
-                            result.push(new_chessboard);
-                        }
-                    }
+                if square & Constants::SEVENTH_RANK > 0 && forward_square_1 & self.get_all_pieces() == 0 && forward_square_2 & self.get_all_pieces() == 0 {
+                    black_pawn_forward(square, forward_square_2, &mut result)
+                }
 
+                // CHECK FOR EN PASSANT
+                if PAWN_ATTACKS[Color::Black as usize][i] & en_passant_square > 0 {
+                    black_en_passant_move(square, en_passant_square, &mut result)
                 }
+                black_pawns -= square;
             }
+
             return result;
         }
 
-        pub fn get_all_pseudo_legal_white_bishop_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let white_bishops = self.white_bishops;
-            let mut square: u64;
-            let mut hit_piece: bool;
-            for i in 0..64 {
-                square =  1 << i;
-
-                if white_bishops & square > 0 {
-                    // CHECK UPRIGHT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(9);
-                    while !overflow && !hit_piece && attacked_square & Constants::A_FILE == 0  {
-                        hit_piece = white_bishop_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(9);
-                    }
-                    // CHECK UPLEFT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(7);
-                    while !overflow && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = white_bishop_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(7);
-                    }
-                    // CHECK DOWNRIGHT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 7;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::A_FILE == 0 {
-                        hit_piece = white_bishop_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 7;
-                    }
-
-                    // CHECK DOWNLEFT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 9 ;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = white_bishop_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 9;
-                    }
+        pub fn get_all_pseudo_legal_white_rook_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut white_rooks = self.white_rooks;
+            while white_rooks > 0 {
+                let square = white_rooks & white_rooks.wrapping_neg();
+                let mut attacked_squares = rook_attacks(square.trailing_zeros() as usize, self.get_all_pieces()) & !self.get_all_white_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    white_slider_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
                 }
+                white_rooks -= square;
             }
             return result;
         }
-        
-        pub fn get_all_pseudo_legal_black_bishop_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let black_bishops = self.black_bishops;
-            let mut square: u64;
-            let mut hit_piece: bool;
-            for i in 0..64 {
-                square =  1 << i;
-
-                if black_bishops & square > 0 {
-                    // CHECK UPRIGHT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(9);
-                    while !overflow && !hit_piece && attacked_square & Constants::A_FILE == 0  {
-                        hit_piece = black_bishop_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(9);
-                    }
-                    // CHECK UPLEFT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(7);
-                    while !overflow && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = black_bishop_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(7);
-                    }
-                    // CHECK DOWNRIGHT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 7;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::A_FILE == 0 {
-                        hit_piece = black_bishop_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 7;
-                    }
 
-                    // CHECK DOWNLEFT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 9 ;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = black_bishop_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 9;
-                    }
+        pub fn get_all_pseudo_legal_black_rook_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut black_rooks = self.black_rooks;
+            while black_rooks > 0 {
+                let square = black_rooks & black_rooks.wrapping_neg();
+                let mut attacked_squares = rook_attacks(square.trailing_zeros() as usize, self.get_all_pieces()) & !self.get_all_black_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    black_slider_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
                 }
+                black_rooks -= square;
             }
             return result;
-
         }
-        
-        pub fn get_all_pseudo_legal_white_queen_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let white_queens = self.white_queens;
-            let mut square: u64;
-            let mut hit_piece: bool;
-            for i in 0..64 {
-                square =  1 << i;
-                // check if queen occupies square
-                if white_queens & square > 0 {
-
-                    // CHECK UP
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(8);
-                    while !overflow && !hit_piece {
-                        
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(8);
-                    }
-
-                    // CHECK DOWN
-                    hit_piece = false;
-                    attacked_square = square >> 8;
-                    while attacked_square > 0 && !hit_piece {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 8;
-                    }
-
-                    // CHECK LEFT
-                    hit_piece = false;
-                    attacked_square = square >> 1;
-                    while (attacked_square & Constants::H_FILE) == 0 && !hit_piece && attacked_square > 0 {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 1;
-                    }
-
-
-                    // CHECK RIGHT
-                    hit_piece = false;
-                    (attacked_square, overflow) = square.overflowing_loss_checked_shl(1);
-                    while (attacked_square & Constants::A_FILE) == 0 && !hit_piece && !overflow {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(1);
-                    }
-
-                    // CHECK UPRIGHT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(9);
-                    while !overflow && !hit_piece && attacked_square & Constants::A_FILE == 0  {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(9);
-                    }
-                    // CHECK UPLEFT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(7);
-                    while !overflow && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(7);
-                    }
-                    // CHECK DOWNRIGHT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 7;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::A_FILE == 0 {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 7;
-                    }
-
-                    // CHECK DOWNLEFT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 9 ;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = white_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 9;
-                    }
-
 
+        pub fn get_all_pseudo_legal_white_knight_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut white_knights = self.white_knights;
+            while white_knights > 0 {
+                let square = white_knights & white_knights.wrapping_neg();
+                let mut attacked_squares = KNIGHT_ATTACKS[square.trailing_zeros() as usize] & !self.get_all_white_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    white_knight_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
                 }
+                white_knights -= square;
             }
             return result;
-
         }
 
-        pub fn get_all_pseudo_legal_black_queen_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let black_queens = self.black_queens;
-            let mut square: u64;
-            let mut hit_piece;
-            for i in 0..64 {
-                square =  1 << i;
-                // check if queen occupies square
-                if black_queens & square > 0 {
-
-                    // CHECK UP
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(8);
-                    while !overflow && !hit_piece {
-                        
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(8);
-                    }
-
-                    // CHECK DOWN
-                    hit_piece = false;
-                    attacked_square = square >> 8;
-                    while attacked_square > 0 && !hit_piece {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 8;
-                    }
-
-                    // CHECK LEFT
-                    hit_piece = false;
-                    attacked_square = square >> 1;
-                    while (attacked_square & Constants::H_FILE) == 0 && !hit_piece && attacked_square > 0 {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 1;
-                    }
-
-
-                    // CHECK RIGHT
-                    hit_piece = false;
-                    (attacked_square, overflow) = square.overflowing_loss_checked_shl(1);
-                    while (attacked_square & Constants::A_FILE) == 0 && !hit_piece && !overflow {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(1);
-                    }
-
-                    // CHECK UPRIGHT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(9);
-                    while !overflow && !hit_piece && attacked_square & Constants::A_FILE == 0  {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(9);
-                    }
-                    // CHECK UPLEFT
-                    hit_piece = false;
-                    let (mut attacked_square, mut overflow) = square.overflowing_loss_checked_shl(7);
-                    while !overflow && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        (attacked_square, overflow) = attacked_square.overflowing_loss_checked_shl(7);
-                    }
-                    // CHECK DOWNRIGHT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 7;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::A_FILE == 0 {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 7;
-                    }
-
-                    // CHECK DOWNLEFT
-                    hit_piece = false;
-                    let mut attacked_square = square >> 9 ;
-                    while (attacked_square > 0) && !hit_piece && attacked_square & Constants::H_FILE == 0 {
-                        hit_piece = black_queen_move(square, attacked_square, self, &mut result);
-                        attacked_square = attacked_square >> 9;
-                    }
-
-
+        pub fn get_all_pseudo_legal_black_knight_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut black_knights = self.black_knights;
+            while black_knights > 0 {
+                let square = black_knights & black_knights.wrapping_neg();
+                let mut attacked_squares = KNIGHT_ATTACKS[square.trailing_zeros() as usize] & !self.get_all_black_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    black_knight_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
                 }
+                black_knights -= square;
             }
             return result;
         }
-        
-        pub fn get_all_pseudo_legal_white_king_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let white_king = self.white_king;
 
-            // CHECK UP
-            let (mut attacked_square, mut overflow) = white_king.overflowing_loss_checked_shl(8);
-            if !overflow && (self.get_all_white_pieces() & attacked_square) == 0 {            
-                white_king_move(white_king, attacked_square, self, &mut result);
-            }
-
-            // CHECK DOWN
-            attacked_square = white_king >> 8;
-            if attacked_square > 0 && (self.get_all_white_pieces() & attacked_square) == 0 {
-                white_king_move(white_king, attacked_square, self, &mut result);
+        pub fn get_all_pseudo_legal_white_bishop_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut white_bishops = self.white_bishops;
+            while white_bishops > 0 {
+                let square = white_bishops & white_bishops.wrapping_neg();
+                let mut attacked_squares = bishop_attacks(square.trailing_zeros() as usize, self.get_all_pieces()) & !self.get_all_white_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    white_slider_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
+                }
+                white_bishops -= square;
             }
+            return result;
+        }
 
-            // CHECK LEFT
-            attacked_square = white_king >> 1;
-            if (attacked_square & Constants::H_FILE) == 0 && (self.get_all_white_pieces() & attacked_square) == 0 && attacked_square > 0 {
-                white_king_move(white_king, attacked_square, self, &mut result);
+        pub fn get_all_pseudo_legal_black_bishop_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut black_bishops = self.black_bishops;
+            while black_bishops > 0 {
+                let square = black_bishops & black_bishops.wrapping_neg();
+                let mut attacked_squares = bishop_attacks(square.trailing_zeros() as usize, self.get_all_pieces()) & !self.get_all_black_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    black_slider_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
+                }
+                black_bishops -= square;
             }
+            return result;
+        }
 
-            // CHECK RIGHT
-            (attacked_square, overflow) = white_king.overflowing_loss_checked_shl(1);
-            if (attacked_square & Constants::A_FILE) == 0 && (self.get_all_white_pieces() & attacked_square) == 0 && !overflow {
-                white_king_move(white_king, attacked_square, self, &mut result);
+        pub fn get_all_pseudo_legal_white_queen_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut white_queens = self.white_queens;
+            while white_queens > 0 {
+                let square = white_queens & white_queens.wrapping_neg();
+                let mut attacked_squares = queen_attacks(square.trailing_zeros() as usize, self.get_all_pieces()) & !self.get_all_white_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    white_slider_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
+                }
+                white_queens -= square;
             }
+            return result;
+        }
 
-            // CHECK UPRIGHT
-            (attacked_square, overflow) = white_king.overflowing_loss_checked_shl(9);
-            if !overflow && (self.get_all_white_pieces() & attacked_square) == 0 && attacked_square & Constants::A_FILE == 0  {
-                white_king_move(white_king, attacked_square, self, &mut result);
-            }
-            // CHECK UPLEFT
-            (attacked_square, overflow) = white_king.overflowing_loss_checked_shl(7);
-            if !overflow && (self.get_all_white_pieces() & attacked_square) == 0 && attacked_square & Constants::H_FILE == 0 {
-                white_king_move(white_king, attacked_square, self, &mut result);
+        pub fn get_all_pseudo_legal_black_queen_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let mut black_queens = self.black_queens;
+            while black_queens > 0 {
+                let square = black_queens & black_queens.wrapping_neg();
+                let mut attacked_squares = queen_attacks(square.trailing_zeros() as usize, self.get_all_pieces()) & !self.get_all_black_pieces();
+                while attacked_squares > 0 {
+                    let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
+                    black_slider_move(square, attacked_square, self, &mut result);
+                    attacked_squares -= attacked_square;
+                }
+                black_queens -= square;
             }
+            return result;
+        }
 
-            // CHECK DOWNRIGHT
-            attacked_square = white_king >> 7;
-            if (attacked_square > 0) && (self.get_all_white_pieces() & attacked_square) == 0 && attacked_square & Constants::A_FILE == 0 {
-                white_king_move(white_king, attacked_square, self, &mut result);
-            }
+        pub fn get_all_pseudo_legal_white_king_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let white_king = self.white_king;
 
-            // CHECK DOWNLEFT
-            attacked_square = white_king >> 9 ;
-            if (attacked_square > 0) && (self.get_all_white_pieces() & attacked_square) == 0 && attacked_square & Constants::H_FILE == 0 {
+            let mut attacked_squares = KING_ATTACKS[white_king.trailing_zeros() as usize] & !self.get_all_white_pieces();
+            while attacked_squares > 0 {
+                let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
                 white_king_move(white_king, attacked_square, self, &mut result);
-            }
-
-            // CHECK CASTLE
-            if !self.white_moved_king {
-                // CHECK IF THERE ARE PIECES ON CASTLING SQUARES AND IF ROOK IS MOVED
-                if(!self.white_moved_A_rook) && (white_king >> 1 & self.get_all_pieces() == 0) && (white_king >> 2 & self.get_all_pieces() == 0) {
-                    if !are_white_short_castling_squares_under_attack(self){
-                        white_short_castle(self, &mut result);
-                    }
-                }
-
-                // CHECK IF THERE ARE PIECES ON CASTLING SQUARES AND IF ROOK IS MOVED
-                if (!self.white_moved_H_rook) && (white_king << 1 & self.get_all_pieces() == 0) && (white_king << 2 & self.get_all_pieces() == 0) {
-                    if !are_white_long_castling_squares_under_attack(self){
-                        white_long_castle(self, &mut result);
-                    }
-                }
+                attacked_squares -= attacked_square;
             }
 
             return result;
         }
-        
-        pub fn get_all_pseudo_legal_black_king_moves(&self) -> Vec<ChessBoard> {
-            let mut result: Vec<ChessBoard> = vec![];
-            let black_king = self.black_king;
-
-            // CHECK UP
-            let (mut attacked_square, mut overflow) = black_king.overflowing_loss_checked_shl(8);
-            if !overflow && (self.get_all_black_pieces() & attacked_square) == 0 {            
-                black_king_move(black_king, attacked_square, self, &mut result);
-            }
-
-            // CHECK DOWN
-            attacked_square = black_king >> 8;
-            if attacked_square > 0 && (self.get_all_black_pieces() & attacked_square) == 0 {
-                black_king_move(black_king, attacked_square, self, &mut result);
-            }
 
-            // CHECK LEFT
-            attacked_square = black_king >> 1;
-            if (attacked_square & Constants::H_FILE) == 0 && (self.get_all_black_pieces() & attacked_square) == 0 && attacked_square > 0 {
-                black_king_move(black_king, attacked_square, self, &mut result);
-            }
+        pub fn get_all_pseudo_legal_black_king_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let black_king = self.black_king;
 
-            // CHECK RIGHT
-            (attacked_square, overflow) = black_king.overflowing_loss_checked_shl(1);
-            if (attacked_square & Constants::A_FILE) == 0 && (self.get_all_black_pieces() & attacked_square) == 0 && !overflow {
+            let mut attacked_squares = KING_ATTACKS[black_king.trailing_zeros() as usize] & !self.get_all_black_pieces();
+            while attacked_squares > 0 {
+                let attacked_square = attacked_squares & attacked_squares.wrapping_neg();
                 black_king_move(black_king, attacked_square, self, &mut result);
+                attacked_squares -= attacked_square;
             }
 
-            // CHECK UPRIGHT
-            (attacked_square, overflow) = black_king.overflowing_loss_checked_shl(9);
-            if !overflow && (self.get_all_black_pieces() & attacked_square) == 0 && attacked_square & Constants::A_FILE == 0  {
-                black_king_move(black_king, attacked_square, self, &mut result);
-            }
-            // CHECK UPLEFT
-            (attacked_square, overflow) = black_king.overflowing_loss_checked_shl(7);
-            if !overflow && (self.get_all_black_pieces() & attacked_square) == 0 && attacked_square & Constants::H_FILE == 0 {
-                black_king_move(black_king, attacked_square, self, &mut result);
-            }
+            return result;
+        }
 
-            // CHECK DOWNRIGHT
-            attacked_square = black_king >> 7;
-            if (attacked_square > 0) && (self.get_all_black_pieces() & attacked_square) == 0 && attacked_square & Constants::A_FILE == 0 {
-                black_king_move(black_king, attacked_square, self, &mut result);
-            }
+        pub fn get_all_pseudo_legal_white_castling_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let (b1, c1, d1, f1, g1) = (0x2, 0x4, 0x8, 0x20, 0x40);
 
-            // CHECK DOWNLEFT
-            attacked_square = black_king >> 9 ;
-            if (attacked_square > 0) && (self.get_all_black_pieces() & attacked_square) == 0 && attacked_square & Constants::H_FILE == 0 {
-                black_king_move(black_king, attacked_square, self, &mut result);
+            // CHECK SHORT CASTLE
+            // f1 and g1 have to be empty, e1, f1 and g1 can't be attacked
+            if self.castling_rights & CastlingRights::WHITE_SHORT > 0 && self.get_all_pieces() & (f1 | g1) == 0 && !are_white_short_castling_squares_under_attack(self) {
+                white_short_castle(self, &mut result);
             }
 
-            // CHECK CASTLE
-            if !self.black_moved_king {
-                // CHECK IF THERE ARE PIECES ON CASTLING SQUARES AND IF ROOK IS MOVED
-                if(!self.black_moved_A_rook) && (black_king >> 1 & self.get_all_pieces() == 0) && (black_king >> 2 & self.get_all_pieces() == 0) {
-                    if !are_black_short_castling_squares_under_attack(self){
-                        black_short_castle(self, &mut result);
-                    }
-                }
-
-                // CHECK IF THERE ARE PIECES ON CASTLING SQUARES AND IF ROOK IS MOVED
-                if (!self.black_moved_H_rook) && (black_king << 1 & self.get_all_pieces() == 0) && (black_king << 2 & self.get_all_pieces() == 0) {
-                    if !are_black_long_castling_squares_under_attack(self){
-                        black_long_castle(self, &mut result);
-                    }
-                }
+            // CHECK LONG CASTLE
+            // b1, c1 and d1 have to be empty, only e1, d1 and c1 can't be attacked
+            if self.castling_rights & CastlingRights::WHITE_LONG > 0 && self.get_all_pieces() & (b1 | c1 | d1) == 0 && !are_white_long_castling_squares_under_attack(self) {
+                white_long_castle(self, &mut result);
             }
 
             return result;
         }
-        
-        pub fn get_all_pseudo_legal_white_moves(&self) -> Vec<ChessBoard> {
-            let mut pseudo_legal_moves: Vec<ChessBoard> = vec![];
+
+        pub fn get_all_pseudo_legal_white_moves(&self) -> Vec<Move> {
+            let mut pseudo_legal_moves: Vec<Move> = vec![];
 
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_white_pawn_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_white_rook_moves());
@@ -1386,110 +893,208 @@ pub mod chessboard {
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_white_bishop_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_white_queen_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_white_king_moves());
+            pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_white_castling_moves());
 
             return pseudo_legal_moves;
         }
 
-        pub fn get_all_pseudo_legal_black_moves(&self) -> Vec<ChessBoard> {
-            let mut pseudo_legal_moves: Vec<ChessBoard> = vec![];
-            // TODO: add other pieces
+        pub fn get_all_pseudo_legal_black_castling_moves(&self) -> Vec<Move> {
+            let mut result: Vec<Move> = vec![];
+            let (b8, c8, d8, f8, g8) = (0x200000000000000, 0x400000000000000, 0x800000000000000, 0x2000000000000000, 0x4000000000000000);
+
+            // CHECK SHORT CASTLE
+            // f8 and g8 have to be empty, e8, f8 and g8 can't be attacked
+            if self.castling_rights & CastlingRights::BLACK_SHORT > 0 && self.get_all_pieces() & (f8 | g8) == 0 && !are_black_short_castling_squares_under_attack(self) {
+                black_short_castle(self, &mut result);
+            }
+
+            // CHECK LONG CASTLE
+            // b8, c8 and d8 have to be empty, only e8, d8 and c8 can't be attacked
+            if self.castling_rights & CastlingRights::BLACK_LONG > 0 && self.get_all_pieces() & (b8 | c8 | d8) == 0 && !are_black_long_castling_squares_under_attack(self) {
+                black_long_castle(self, &mut result);
+            }
+
+            return result;
+        }
+
+        pub fn get_all_pseudo_legal_black_moves(&self) -> Vec<Move> {
+            let mut pseudo_legal_moves: Vec<Move> = vec![];
+
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_pawn_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_rook_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_knight_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_bishop_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_queen_moves());
             pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_king_moves());
+            pseudo_legal_moves.append(&mut self.get_all_pseudo_legal_black_castling_moves());
 
             return pseudo_legal_moves;
         }
 
-        #[allow(unused_parens)]
-        pub fn get_all_legal_white_moves(&self, pseudo_legal_white_moves: Option<&Vec<ChessBoard>>) -> (Vec<ChessBoard>/* , Vec<Vec<ChessBoard>> */) {
-            
-            let mut result: Vec<ChessBoard> = vec![];
-            let _pseudo_legal_white_moves: &Vec<ChessBoard>;
-            let temp ;
-            // let now = Instant::now();
-            if pseudo_legal_white_moves.is_some() {
-                // let now = Instant::now();
-                _pseudo_legal_white_moves = pseudo_legal_white_moves.unwrap();
-                // println!("{}", now.elapsed().as_micros());
+        // Checkers, pinned pieces and the squares that resolve a single check
+        // for the side to move, computed once and shared by every move
+        pub(crate) fn check_info(&self) -> CheckInfo {
+            let (king, enemy, own_pieces) = if self.white_to_move {
+                (self.white_king, Color::Black, self.get_all_white_pieces())
             } else {
-                // println!("A");
-                temp = self.get_all_pseudo_legal_white_moves();
-                _pseudo_legal_white_moves = &temp;
+                (self.black_king, Color::White, self.get_all_black_pieces())
+            };
+            let (enemy_pieces, enemy_bishops_queens, enemy_rooks_queens) = match enemy {
+                Color::White => (self.get_all_white_pieces(), self.white_bishops | self.white_queens, self.white_rooks | self.white_queens),
+                Color::Black => (self.get_all_black_pieces(), self.black_bishops | self.black_queens, self.black_rooks | self.black_queens),
+            };
+            let king_index = king.trailing_zeros() as usize;
+            let occupancy = self.get_all_pieces();
+
+            let checkers = self.attackers_to(king) & enemy_pieces;
+
+            // sliders that would attack the king on an empty board pin the single own piece in between
+            let mut pinned: u64 = 0;
+            let mut snipers = (rook_attacks(king_index, 0) & enemy_rooks_queens) | (bishop_attacks(king_index, 0) & enemy_bishops_queens);
+            while snipers > 0 {
+                let sniper = snipers.trailing_zeros() as usize;
+                let blockers = BETWEEN[king_index][sniper] & occupancy;
+                if blockers.count_ones() == 1 {
+                    pinned |= blockers & own_pieces;
+                }
+                snipers &= snipers - 1;
             }
 
+            let check_mask = match checkers.count_ones() {
+                0 => u64::MAX,
+                1 => BETWEEN[king_index][checkers.trailing_zeros() as usize] | checkers,
+                _ => 0,
+            };
 
-            let mut is_checked_after_white_move: bool;
-            let mut pseudo_legal_black_moves;
+            return CheckInfo { checkers, pinned, check_mask };
+        }
 
-            // this was returning as second variable in struct
-            // let mut pseudo_legal_black_moves_for_each_white_move = vec![];
+        // Only valid for pseudo-legal moves of the side to move
+        pub(crate) fn is_legal(&self, mov: Move, check_info: &CheckInfo) -> bool {
+            let (from, to) = (mov.get_from_square(), mov.get_to_square());
+            let (king, enemy, enemy_pawns, enemy_knights, enemy_bishops_queens, enemy_rooks_queens) = if self.white_to_move {
+                (self.white_king, Color::Black, self.black_pawns, self.black_knights, self.black_bishops | self.black_queens, self.black_rooks | self.black_queens)
+            } else {
+                (self.black_king, Color::White, self.white_pawns, self.white_knights, self.white_bishops | self.white_queens, self.white_rooks | self.white_queens)
+            };
+            let king_index = king.trailing_zeros() as usize;
 
-            for mov in _pseudo_legal_white_moves {
-                // let now = Instant::now();
-                (is_checked_after_white_move, pseudo_legal_black_moves) = mov.is_white_king_checked();
-                // let elapsed = now.elapsed();
-                // println!("is king checked? {}", elapsed.as_micros());
+            if from == king {
+                // the castling generator already checked every square the king crosses
+                if mov.is_castle() {
+                    return true;
+                }
+                // take the king off the board so it can't hide behind itself on a checking ray
+                return !self.is_square_attacked_with_occupancy(to, enemy, self.get_all_pieces() ^ king);
+            }
 
-                if !is_checked_after_white_move {
-                    result.push(*mov);
-                    // pseudo_legal_black_moves_for_each_white_move.push(pseudo_legal_black_moves);
-                } 
+            // in double check only the king can move
+            if check_info.checkers.count_ones() > 1 {
+                return false;
             }
 
-            return result;
+            if mov.is_en_passant() {
+                // both pawns leave the rank at once, which can uncover a slider on the king
+                let captured = if self.white_to_move { to >> 8 } else { to << 8 };
+                let occupancy = (self.get_all_pieces() ^ from ^ captured) | to;
+                return bishop_attacks(king_index, occupancy) & enemy_bishops_queens == 0
+                    && rook_attacks(king_index, occupancy) & enemy_rooks_queens == 0
+                    && check_info.checkers & !captured & (enemy_pawns | enemy_knights) == 0;
+            }
+
+            if to & check_info.check_mask == 0 {
+                return false;
+            }
+
+            // a pinned piece may only move along the pin
+            return from & check_info.pinned == 0 || LINE[king_index][to.trailing_zeros() as usize] & from > 0;
         }
 
-        #[allow(unused_parens)]
-        pub fn get_all_legal_black_moves(&self, pseudo_legal_black_moves: Option<&Vec<ChessBoard>>) -> (Vec<ChessBoard>/* , Vec<Vec<ChessBoard>> */) {
+        pub fn get_all_legal_white_moves(&self, pseudo_legal_white_moves: Option<&Vec<Move>>) -> Vec<Move> {
+            debug_assert!(self.white_to_move);
+            let temp;
+            let _pseudo_legal_white_moves: &Vec<Move> = match pseudo_legal_white_moves {
+                Some(moves) => moves,
+                None => {
+                    temp = self.get_all_pseudo_legal_white_moves();
+                    &temp
+                }
+            };
 
-            let mut result: Vec<ChessBoard> = vec![];
-            // let now = Instant::now();
+            let check_info = self.check_info();
+            return _pseudo_legal_white_moves.iter().copied().filter(|mov| self.is_legal(*mov, &check_info)).collect();
+        }
+
+        pub fn get_all_legal_black_moves(&self, pseudo_legal_black_moves: Option<&Vec<Move>>) -> Vec<Move> {
+            debug_assert!(!self.white_to_move);
+            let temp;
+            let _pseudo_legal_black_moves: &Vec<Move> = match pseudo_legal_black_moves {
+                Some(moves) => moves,
+                None => {
+                    temp = self.get_all_pseudo_legal_black_moves();
+                    &temp
+                }
+            };
 
-            let _pseudo_legal_black_moves: &Vec<ChessBoard>;
-            let temp ;
+            let check_info = self.check_info();
+            return _pseudo_legal_black_moves.iter().copied().filter(|mov| self.is_legal(*mov, &check_info)).collect();
+        }
 
-            if pseudo_legal_black_moves.is_some() {
-                _pseudo_legal_black_moves = pseudo_legal_black_moves.unwrap();
+        pub fn legal_moves(&self, pseudo_legal: Option<&Vec<Move>>) -> Vec<Move> {
+            if self.white_to_move {
+                return self.get_all_legal_white_moves(pseudo_legal);
             } else {
-                // println!("B");
-                temp = self.get_all_pseudo_legal_black_moves();
-                _pseudo_legal_black_moves = &temp;
+                return self.get_all_legal_black_moves(pseudo_legal);
             }
+        }
 
-            let mut is_checked_after_black_move: bool;
-            let mut pseudo_legal_white_moves;
-            // let mut pseudo_legal_white_moves_for_each_black_move = vec![];
-
-            for mov in _pseudo_legal_black_moves {
-                (is_checked_after_black_move, pseudo_legal_white_moves) = mov.is_black_king_checked();
-
-                if !is_checked_after_black_move {
-                    result.push(*mov);
-                    // pseudo_legal_white_moves_for_each_black_move.push(pseudo_legal_white_moves);
-                }
+        pub fn is_in_check(&self) -> bool {
+            if self.white_to_move {
+                return self.is_white_king_checked();
+            } else {
+                return self.is_black_king_checked();
             }
+        }
 
-            return result;
+        pub fn is_checkmate(&self) -> bool {
+            return self.is_in_check() && self.legal_moves(None).is_empty();
+        }
 
+        pub fn is_stalemate(&self) -> bool {
+            return !self.is_in_check() && self.legal_moves(None).is_empty();
         }
-        
-        #[allow(unused_parens)]
-        pub fn legal_moves(&self, pseudo_legal: Option<&Vec<ChessBoard>>) -> (Vec<ChessBoard>/* , Vec<Vec<ChessBoard>> */){
-            let param;
-            if pseudo_legal.is_some() {
-                param = pseudo_legal;
-            } else {
-                param = None
+
+        // Neither side can ever mate: bare kings, a single minor piece,
+        // or bishops that all stand on squares of the same color
+        pub fn is_insufficient_material(&self) -> bool {
+            let heavy_pieces_and_pawns = self.white_pawns | self.black_pawns | self.white_rooks | self.black_rooks | self.white_queens | self.black_queens;
+            if heavy_pieces_and_pawns > 0 {
+                return false;
             }
 
-            if self.white_to_move {
-                return self.get_all_legal_white_moves(param);
-            } else {
-                return self.get_all_legal_black_moves(param); 
+            let knights = self.white_knights | self.black_knights;
+            let bishops = self.white_bishops | self.black_bishops;
+            if (knights | bishops).count_ones() <= 1 {
+                return true;
+            }
+            return knights == 0 && (bishops & Constants::LIGHT_SQUARES == 0 || bishops & Constants::DARK_SQUARES == 0);
+        }
+
+        pub fn status(&self) -> GameStatus {
+            if self.legal_moves(None).is_empty() {
+                if !self.is_in_check() {
+                    return GameStatus::Stalemate;
+                }
+                let winner = if self.white_to_move { Color::Black } else { Color::White };
+                return GameStatus::Checkmate { winner };
+            }
+            if self.is_insufficient_material() {
+                return GameStatus::InsufficientMaterial;
+            }
+            if self.is_fifty_move_draw() {
+                return GameStatus::FiftyMoveRule;
             }
+            return GameStatus::Ongoing;
         }
 
         pub fn whiteDoubledPawns(&self) -> u32 {
@@ -1520,152 +1125,105 @@ pub mod chessboard {
             return (all_pieces & mask).count_ones();
         }
 
-        // pub fn perft(&self, depth: u64) {
-        //     if depth == 0 {
-        //         return;
-        //     }
-
-        //     if self.white_to_move {
-        //         for pos in self.get_all_legal_white_moves(None).0{
-        //             pos.perft(depth - 1);
-        //         }
-        //     } else {
-        //         for pos in self.get_all_legal_black_moves(None).0{
-        //             pos.perft(depth - 1);
-        //         }
-        //     }
-        // }
-
-        /* This was before removing Vec<Vec<>> */
-        // pub fn _perft(&self, depth: u64,  currDepth: u64, numberOfPositions: u64, pseudo_legal: Option<&Vec<ChessBoard>>) -> u64{
-        //     let mut _numberOfPositions= numberOfPositions;
-        //     let param;
-        //     if pseudo_legal.is_some() {
-        //         param = pseudo_legal;
-        //     } else {
-        //         param = None;
-        //     }
-        //     // let mut currDepth = currDepth;
-        //     if depth == currDepth {
-        //         _numberOfPositions += 1;
-                
-        //     } else if depth < currDepth {
-        //         return 0;
-        //     } else {
-        //         if self.white_to_move {
-        //             let (positions, next) = self.get_all_legal_white_moves(param);
-        //             for (i, pos) in positions.iter().enumerate() {
-        //                 _numberOfPositions = pos._perft(depth, currDepth + 1, _numberOfPositions, Some(&next[i]));
-        //             }
-        //         } else {
-        //             let (positions, next) = self.get_all_legal_black_moves(param);
-        //             for (i, pos) in positions.iter().enumerate() {
-        //                 _numberOfPositions = pos._perft(depth, currDepth + 1, _numberOfPositions, Some(&next[i]));
-        //             }
-        //         }
-        //     }
-
-        //     // currDepth -= 1;
-        //     // println!("{}", _numberOfPositions);
-        //     return _numberOfPositions;
-
-        // }
-
-        pub fn _perft(&self, depth: u64,  currDepth: u64, numberOfPositions: u64) -> u64{
-            let mut _numberOfPositions= numberOfPositions;
-
-            // let mut currDepth = currDepth;
-            if depth == currDepth {
-                _numberOfPositions += 1;
-                
-            } else if depth < currDepth {
-                return 0;
-            } else {
-                if self.white_to_move {
-                    let positions = self.get_all_legal_white_moves(None);
-                    for (i, pos) in positions.iter().enumerate() {
-                        _numberOfPositions = pos._perft(depth, currDepth + 1, _numberOfPositions);
-                    }
-                } else {
-                    let positions = self.get_all_legal_black_moves(None);
-                    for (i, pos) in positions.iter().enumerate() {
-                        _numberOfPositions = pos._perft(depth, currDepth + 1, _numberOfPositions);
-                    }
-                }
+        pub fn _perft(&mut self, depth: u64) -> u64 {
+            if depth == 0 {
+                return 1;
             }
 
-            // currDepth -= 1;
-            // println!("{}", _numberOfPositions);
-            return _numberOfPositions;
+            let mut numberOfPositions = 0;
+            for mov in self.legal_moves(None) {
+                let undo = self.make_move(mov);
+                numberOfPositions += self._perft(depth - 1);
+                self.unmake_move(mov, undo);
+            }
 
+            return numberOfPositions;
         }
 
         pub fn perft(&self, depth: u64) -> (HashMap<ChessBoard, u64>, u64) {
             let mut result = HashMap::new();
             let mut total = 0;
-            let positions ;
-            if self.white_to_move {
-                positions = self.get_all_legal_white_moves(None);
-            } else {
-                positions = self.get_all_legal_black_moves(None);
-            }
 
-            for (i, pos) in positions.iter().enumerate() {
-                let num_of_positions = pos._perft(depth - 1, 0, 0);
+            for mov in self.legal_moves(None) {
+                let mut chessboard = *self;
+                chessboard.make_move(mov);
+                let num_of_positions = chessboard._perft(depth - 1);
                 total += num_of_positions;
-                result.insert(*pos, num_of_positions);
+                result.insert(chessboard, num_of_positions);
             }
 
             return (result, total);
         }
 
-        pub fn _minimax(&self, depth: u64) -> f32 {
+        // perft split up by root move, sorted by the moves in coordinate notation
+        // (e2e4) so the output lines up with the divide of other engines
+        pub fn divide(&self, depth: u64) -> Vec<(Move, u64)> {
             if depth == 0 {
-                return self.evaluate();
+                return vec![];
             }
 
-            // let param;
-            // if pseudo_legal.is_some() {
-            //     param = pseudo_legal;
-            // } else {
-            //     param = None;
-            // }
+            let mut chessboard = *self;
+            let mut result: Vec<(Move, u64)> = vec![];
+            for mov in self.legal_moves(None) {
+                let undo = chessboard.make_move(mov);
+                result.push((mov, chessboard._perft(depth - 1)));
+                chessboard.unmake_move(mov, undo);
+            }
 
-            if self.white_to_move {
-                let mut value = f32::NEG_INFINITY;
-                let moves = self.get_all_legal_white_moves(None);
-                for (i, mov) in moves.iter().enumerate() {
-                    value = f32::max(value, mov._minimax(depth - 1));
-                }
-                return value;
-            } else {
-                let mut value = f32::INFINITY;
-                let moves = self.get_all_legal_black_moves(None);
-        
-                for (i, mov) in moves.iter().enumerate() {
-                    value = f32::min(value, mov._minimax(depth - 1));
+            result.sort_by_key(|(mov, _)| mov.to_uci());
+            return result;
+        }
+
+        pub fn _minimax(&mut self, depth: u64) -> f32 {
+            if depth == 0 {
+                return self.evaluate();
+            }
+
+            let moves = self.legal_moves(None);
+            if moves.is_empty() {
+                if !self.is_in_check() {
+                    return 0.0;
                 }
-                return value;
+                // the remaining depth is larger the sooner the mate happens
+                let mate_score = MATE_SCORE + depth as f32;
+                return if self.white_to_move { -mate_score } else { mate_score };
             }
+            if self.is_insufficient_material() || self.is_fifty_move_draw() {
+                return 0.0;
+            }
+
+            let mut value = if self.white_to_move { f32::NEG_INFINITY } else { f32::INFINITY };
+            for mov in moves {
+                let undo = self.make_move(mov);
+                let child_value = self._minimax(depth - 1);
+                self.unmake_move(mov, undo);
 
+                if self.white_to_move {
+                    value = f32::max(value, child_value);
+                } else {
+                    value = f32::min(value, child_value);
+                }
+            }
+            return value;
         }
 
 
-        pub fn minimax(&self, depth: u64) -> Vec<(ChessBoard, f32)> {
-            let mut res: Vec<(ChessBoard, f32)> = vec![];
+        pub fn minimax(&self, depth: u64) -> Vec<(Move, f32)> {
+            let mut res: Vec<(Move, f32)> = vec![];
 
-            let moves = self.legal_moves(None);
-            for (_, mov) in moves.iter().enumerate() {
-                res.push((*mov, mov._minimax(depth - 1)));
+            let mut chessboard = *self;
+            for mov in self.legal_moves(None) {
+                let undo = chessboard.make_move(mov);
+                res.push((mov, chessboard._minimax(depth - 1)));
+                chessboard.unmake_move(mov, undo);
             }
-            
+
 
             res.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
             return res;
         }
 
         pub fn evaluate(&self) -> f32 {
-            let (wK, bK) = (self.white_king.count_ones() as i32, self.black_king.count_ones() as i32);
             let (wQ, bQ) = (self.white_queens.count_ones() as i32, self.black_queens.count_ones() as i32);
             let (wR, bR) = (self.white_rooks.count_ones() as i32, self.black_rooks.count_ones() as i32);
             let (wB, bB) = (self.white_bishops.count_ones() as i32, self.black_bishops.count_ones() as i32);
@@ -1678,11 +1236,10 @@ pub mod chessboard {
             let (wBlocked, bBlocked) = (self.whiteBlockedPawns() as i32, self.blackBlockedPawns() as i32);
 
             // TODO: add mobility
-            let evaluation :f32 = (200 * (wK - bK)
-                                + 9 * (wQ - bQ)
+            let evaluation :f32 = (9 * (wQ - bQ)
                                 + 5 * (wR - bR)
                                 + 3 * (wB - bB + wN - bN)
-                                + 1 * (wP - bP)) as f32
+                                + (wP - bP)) as f32
                                 - 0.5 * (wDoubled - bDoubled + wBlocked - bBlocked) as f32;
 
             return evaluation;
@@ -1692,6 +1249,28 @@ pub mod chessboard {
 
     
     
+    pub struct CastlingRights;
+    impl CastlingRights {
+        pub const WHITE_SHORT: u8 = 1; // K
+        pub const WHITE_LONG: u8 = 2;  // Q
+        pub const BLACK_SHORT: u8 = 4; // k
+        pub const BLACK_LONG: u8 = 8;  // q
+        pub const ALL: u8 = 15;
+
+        // rights lost once a move starts or ends on the square
+        pub fn revoked_by(square: u64) -> u8 {
+            match square {
+                0x10 => Self::WHITE_SHORT | Self::WHITE_LONG,               // e1
+                0x80 => Self::WHITE_SHORT,                                  // h1
+                0x1 => Self::WHITE_LONG,                                    // a1
+                0x1000000000000000 => Self::BLACK_SHORT | Self::BLACK_LONG, // e8
+                0x8000000000000000 => Self::BLACK_SHORT,                    // h8
+                0x100000000000000 => Self::BLACK_LONG,                      // a8
+                _ => 0,
+            }
+        }
+    }
+
     pub struct Constants;
     impl Constants {
         pub const A_FILE: u64 = 0x0101010101010101;
@@ -0,0 +1,254 @@
+// Forsyth-Edwards Notation
+//
+// rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1
+// piece placement (8th rank first), side to move, castling rights,
+// en passant target square, halfmove clock, fullmove number
+
+use std::fmt;
+
+use crate::chess_move::Piece;
+use crate::chessboard::chessboard::{CastlingRights, ChessBoard, Constants, EnumSquare};
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FenError {
+    MissingField(&'static str),
+    TooManyFields(usize),
+    WrongNumberOfRanks(usize),
+    InvalidPiece { rank: u8, character: char },
+    WrongRankLength { rank: u8, squares: u32 },
+    PawnOnBackRank { rank: u8 },
+    WrongKingCount { white: bool, count: u32 },
+    InvalidSideToMove(String),
+    InvalidCastlingRights(String),
+    InvalidEnPassantSquare(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::MissingField(field) => write!(f, "missing {} field", field),
+            FenError::TooManyFields(count) => write!(f, "expected at most 6 fields, found {}", count),
+            FenError::WrongNumberOfRanks(count) => write!(f, "expected 8 ranks in piece placement, found {}", count),
+            FenError::InvalidPiece { rank, character } => write!(f, "invalid piece '{}' on rank {}", character, rank),
+            FenError::WrongRankLength { rank, squares } => write!(f, "rank {} describes {} squares instead of 8", rank, squares),
+            FenError::PawnOnBackRank { rank } => write!(f, "pawn placed on rank {}", rank),
+            FenError::WrongKingCount { white, count } => {
+                write!(f, "expected one {} king, found {}", if *white { "white" } else { "black" }, count)
+            }
+            FenError::InvalidSideToMove(field) => write!(f, "invalid side to move '{}', expected 'w' or 'b'", field),
+            FenError::InvalidCastlingRights(field) => write!(f, "invalid castling rights '{}'", field),
+            FenError::InvalidEnPassantSquare(field) => write!(f, "invalid en passant square '{}'", field),
+            FenError::InvalidHalfmoveClock(field) => write!(f, "invalid halfmove clock '{}'", field),
+            FenError::InvalidFullmoveNumber(field) => write!(f, "invalid fullmove number '{}'", field),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+pub(crate) fn piece_from_char(character: char) -> Option<Piece> {
+    match character.to_ascii_lowercase() {
+        'p' => Some(Piece::Pawn),
+        'n' => Some(Piece::Knight),
+        'b' => Some(Piece::Bishop),
+        'r' => Some(Piece::Rook),
+        'q' => Some(Piece::Queen),
+        'k' => Some(Piece::King),
+        _ => None,
+    }
+}
+
+pub(crate) fn piece_to_char(piece: Piece, white: bool) -> char {
+    let character = match piece {
+        Piece::Pawn => 'p',
+        Piece::Knight => 'n',
+        Piece::Bishop => 'b',
+        Piece::Rook => 'r',
+        Piece::Queen => 'q',
+        Piece::King => 'k',
+    };
+    if white { character.to_ascii_uppercase() } else { character }
+}
+
+impl ChessBoard {
+    pub fn from_fen(fen: &str) -> Result<ChessBoard, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() > 6 {
+            return Err(FenError::TooManyFields(fields.len()));
+        }
+
+        let placement = fields.first().ok_or(FenError::MissingField("piece placement"))?;
+        let side_to_move = fields.get(1).ok_or(FenError::MissingField("side to move"))?;
+        let castling = fields.get(2).ok_or(FenError::MissingField("castling rights"))?;
+        let en_passant = fields.get(3).ok_or(FenError::MissingField("en passant square"))?;
+
+        let mut chessboard = ChessBoard::new();
+        for piece in [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King] {
+            *chessboard.white_bitboard_mut(piece) = 0;
+            *chessboard.black_bitboard_mut(piece) = 0;
+        }
+
+        // PIECE PLACEMENT
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::WrongNumberOfRanks(ranks.len()));
+        }
+
+        for (i, rank_text) in ranks.iter().enumerate() {
+            let rank = 8 - i as u8;
+            let mut file: u32 = 0;
+            for character in rank_text.chars() {
+                if let Some(empty) = character.to_digit(10) {
+                    if empty == 0 || empty > 8 {
+                        return Err(FenError::InvalidPiece { rank, character });
+                    }
+                    file += empty;
+                    continue;
+                }
+
+                let piece = piece_from_char(character).ok_or(FenError::InvalidPiece { rank, character })?;
+                if file >= 8 {
+                    return Err(FenError::WrongRankLength { rank, squares: file + 1 });
+                }
+                if piece == Piece::Pawn && (rank == 1 || rank == 8) {
+                    return Err(FenError::PawnOnBackRank { rank });
+                }
+
+                let square: u64 = 1 << ((rank as u32 - 1) * 8 + file);
+                if character.is_ascii_uppercase() {
+                    *chessboard.white_bitboard_mut(piece) += square;
+                } else {
+                    *chessboard.black_bitboard_mut(piece) += square;
+                }
+                file += 1;
+            }
+            if file != 8 {
+                return Err(FenError::WrongRankLength { rank, squares: file });
+            }
+        }
+
+        if chessboard.white_king.count_ones() != 1 {
+            return Err(FenError::WrongKingCount { white: true, count: chessboard.white_king.count_ones() });
+        }
+        if chessboard.black_king.count_ones() != 1 {
+            return Err(FenError::WrongKingCount { white: false, count: chessboard.black_king.count_ones() });
+        }
+
+        // SIDE TO MOVE
+        chessboard.white_to_move = match *side_to_move {
+            "w" => true,
+            "b" => false,
+            _ => return Err(FenError::InvalidSideToMove(side_to_move.to_string())),
+        };
+
+        // CASTLING RIGHTS
+        chessboard.castling_rights = 0;
+        if *castling != "-" {
+            for character in castling.chars() {
+                // the king and the rook have to be on their starting squares
+                let (right, king, king_square, rooks, rook_square) = match character {
+                    'K' => (CastlingRights::WHITE_SHORT, chessboard.white_king, 0x10, chessboard.white_rooks, 0x80),
+                    'Q' => (CastlingRights::WHITE_LONG, chessboard.white_king, 0x10, chessboard.white_rooks, 0x1),
+                    'k' => (CastlingRights::BLACK_SHORT, chessboard.black_king, 0x1000000000000000, chessboard.black_rooks, 0x8000000000000000),
+                    'q' => (CastlingRights::BLACK_LONG, chessboard.black_king, 0x1000000000000000, chessboard.black_rooks, 0x100000000000000),
+                    _ => return Err(FenError::InvalidCastlingRights(castling.to_string())),
+                };
+                // every right may appear only once
+                if chessboard.castling_rights & right > 0 || king & king_square == 0 || rooks & rook_square == 0 {
+                    return Err(FenError::InvalidCastlingRights(castling.to_string()));
+                }
+                chessboard.castling_rights |= right;
+            }
+        }
+
+        // EN PASSANT
+        if *en_passant != "-" {
+            let invalid = || FenError::InvalidEnPassantSquare(en_passant.to_string());
+            let target_square = EnumSquare::from_name(en_passant).ok_or_else(invalid)?;
+            let target: u64 = target_square.bitboard();
+
+            // the square has to be empty and sit right behind a pawn that just double pushed
+            let double_pushed = if chessboard.white_to_move {
+                target & (Constants::FIFTH_RANK << 8) > 0 && chessboard.black_pawns & (target >> 8) > 0 && chessboard.get_all_pieces() & (target | target << 8) == 0
+            } else {
+                target & (Constants::FOURTH_RANK >> 8) > 0 && chessboard.white_pawns & (target << 8) > 0 && chessboard.get_all_pieces() & (target | target >> 8) == 0
+            };
+            if !double_pushed {
+                return Err(invalid());
+            }
+            chessboard.en_passant_square = Some(target_square.index());
+        }
+
+        // HALFMOVE CLOCK AND FULLMOVE NUMBER (optional, as in EPD)
+        if let Some(halfmove_clock) = fields.get(4) {
+            chessboard.halfmove_clock = halfmove_clock.parse().map_err(|_| FenError::InvalidHalfmoveClock(halfmove_clock.to_string()))?;
+        }
+        if let Some(fullmove_number) = fields.get(5) {
+            chessboard.fullmove_number = match fullmove_number.parse() {
+                Ok(number) if number > 0 => number,
+                _ => return Err(FenError::InvalidFullmoveNumber(fullmove_number.to_string())),
+            };
+        }
+
+        chessboard.zobrist_key = chessboard.compute_zobrist_key();
+        return Ok(chessboard);
+    }
+
+    pub fn to_fen(self) -> String {
+        let mut fen = String::new();
+
+        // PIECE PLACEMENT
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                let square: u64 = 1 << (rank * 8 + file);
+                let piece = match (self.get_white_piece(square), self.get_black_piece(square)) {
+                    (Some(piece), _) => piece_to_char(piece, true),
+                    (_, Some(piece)) => piece_to_char(piece, false),
+                    (None, None) => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+                if empty > 0 {
+                    fen.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                fen.push(piece);
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        // SIDE TO MOVE
+        fen.push_str(if self.white_to_move { " w " } else { " b " });
+
+        // CASTLING RIGHTS
+        let mut castling = String::new();
+        if self.castling_rights & CastlingRights::WHITE_SHORT > 0 { castling.push('K'); }
+        if self.castling_rights & CastlingRights::WHITE_LONG > 0 { castling.push('Q'); }
+        if self.castling_rights & CastlingRights::BLACK_SHORT > 0 { castling.push('k'); }
+        if self.castling_rights & CastlingRights::BLACK_LONG > 0 { castling.push('q'); }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        fen.push_str(&castling);
+
+        // EN PASSANT
+        match self.en_passant_square {
+            Some(target) => fen.push_str(&format!(" {}", EnumSquare::ALL[target as usize])),
+            None => fen.push_str(" -"),
+        }
+
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
+        return fen;
+    }
+}
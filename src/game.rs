@@ -0,0 +1,147 @@
+// Game
+//
+// A ChessBoard only knows the current position. Game keeps the position it
+// started from together with every move played since and the Zobrist key of
+// every position reached, which is what undo and the repetition rules need.
+
+use std::fmt;
+
+use crate::chess_move::Move;
+use crate::chessboard::chessboard::{ChessBoard, GameStatus, UndoInfo};
+use crate::fen::FenError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IllegalMove(pub Move);
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "illegal move {}", self.0)
+    }
+}
+
+impl std::error::Error for IllegalMove {}
+
+#[derive(Clone, Debug)]
+pub struct Game {
+    board: ChessBoard,
+    starting_position: ChessBoard,
+    moves: Vec<Move>,
+    undo_stack: Vec<UndoInfo>,
+    // one key per position reached, the starting position included
+    position_keys: Vec<u64>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Self {
+        return Self::from_board(ChessBoard::new());
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        return Ok(Self::from_board(ChessBoard::from_fen(fen)?));
+    }
+
+    pub fn from_board(board: ChessBoard) -> Self {
+        Self {
+            board,
+            starting_position: board,
+            moves: vec![],
+            undo_stack: vec![],
+            position_keys: vec![board.zobrist_key],
+        }
+    }
+
+    pub fn board(&self) -> &ChessBoard {
+        &self.board
+    }
+
+    pub fn starting_position(&self) -> &ChessBoard {
+        &self.starting_position
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    // Zobrist key of every position reached, the starting position first and the current one last
+    pub(crate) fn position_keys(&self) -> &[u64] {
+        &self.position_keys
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        return self.board.legal_moves(None);
+    }
+
+    pub fn play(&mut self, mov: Move) -> Result<(), IllegalMove> {
+        if !self.legal_moves().contains(&mov) {
+            return Err(IllegalMove(mov));
+        }
+
+        let undo = self.board.make_move(mov);
+        self.moves.push(mov);
+        self.undo_stack.push(undo);
+        self.position_keys.push(self.board.zobrist_key);
+        return Ok(());
+    }
+
+    // Takes back the last move, None at the starting position
+    pub fn undo(&mut self) -> Option<Move> {
+        let mov = self.moves.pop()?;
+        let undo = self.undo_stack.pop()?;
+        self.board.unmake_move(mov, undo);
+        self.position_keys.pop();
+        return Some(mov);
+    }
+
+    // How often the current position has occurred, counting itself
+    pub fn repetition_count(&self) -> usize {
+        let current = self.board.zobrist_key;
+        // a capture or pawn move can never be undone, so only positions since the
+        // last one can repeat, and only every second one has the same side to move
+        let reversible = (self.board.halfmove_clock as usize).min(self.position_keys.len() - 1);
+        return self.position_keys.iter().rev().take(reversible + 1).step_by(2).filter(|key| **key == current).count();
+    }
+
+    // Either player may claim a draw
+    pub fn is_threefold_repetition(&self) -> bool {
+        return self.repetition_count() >= 3;
+    }
+
+    // The game is drawn automatically
+    pub fn is_fivefold_repetition(&self) -> bool {
+        return self.repetition_count() >= 5;
+    }
+
+    pub fn is_fifty_move_rule(&self) -> bool {
+        return self.board.is_fifty_move_draw();
+    }
+
+    pub fn is_seventy_five_move_rule(&self) -> bool {
+        return self.board.halfmove_clock >= 150;
+    }
+
+    pub fn status(&self) -> GameStatus {
+        let status = self.board.status();
+        // a mate on the move that reaches the limit still counts
+        if matches!(status, GameStatus::Checkmate { .. } | GameStatus::Stalemate | GameStatus::InsufficientMaterial) {
+            return status;
+        }
+
+        // automatic draws first, then the ones that have to be claimed
+        if self.is_fivefold_repetition() {
+            return GameStatus::FivefoldRepetition;
+        }
+        if self.is_seventy_five_move_rule() {
+            return GameStatus::SeventyFiveMoveRule;
+        }
+        if self.is_threefold_repetition() {
+            return GameStatus::ThreefoldRepetition;
+        }
+        return status;
+    }
+}
@@ -0,0 +1,34 @@
+//! Bitboard chess engine: board representation, move generation,
+//! evaluation and search. The `ChessEngine` binary is a thin consumer of it.
+//!
+//! The modules are private, the public API is what gets re-exported below.
+
+#![allow(non_snake_case, clippy::needless_return)]
+
+mod attacks;
+mod chess_move;
+mod chessboard;
+mod fen;
+mod game;
+mod magic;
+mod perft;
+mod pgn;
+mod san;
+mod search;
+mod uci;
+mod zobrist;
+
+mod white_utils;
+mod black_utils;
+
+pub use attacks::{KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS};
+pub use chess_move::{Color, Move, MoveFlags, Piece, PROMOTION_PIECES};
+pub use chessboard::chessboard::{CastlingRights, ChessBoard, Constants, EnumSquare, GameStatus, UndoInfo, MATE_SCORE};
+pub use fen::{FenError, STARTING_FEN};
+pub use game::{Game, IllegalMove};
+pub use magic::{bishop_attacks, queen_attacks, rook_attacks};
+pub use perft::PerftStats;
+pub use pgn::{parse_pgn, write_pgn, PgnError, PgnGame};
+pub use san::SanError;
+pub use search::{search, SearchInfo, SearchLimits};
+pub use uci::{run_uci, uci_loop, UciMoveError};
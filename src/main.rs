@@ -1,163 +1,333 @@
-#![allow(non_snake_case)]
-
-mod chessboard;
-mod white_utils;
-mod black_utils;
-use std::{vec, env, time::Instant, collections::HashMap};
-
-use crate::chessboard::chessboard::ChessBoard;
-
-const DEPTH: u64 = 4;
-fn main() {
-    env::set_var("RUST_BACKTRACE", "full");
-    // env::set_var("RUST_MIN_STACK", "33554432");
-    let mut cb = ChessBoard::new();
-    
-
-    // cb.white_queens = 0x1000000000000;
-    // cb.black_pawns = 0xFE000000000000;
-    // cb.white_to_move = false;
-
-    // cb.black_rooks = 0x8001000000000000;
-    // cb.black_pawns = 0xFE000000000000;
-    // cb.white_queens = 0;
-
-    // cb.white_queens = 0x8000000;
-
-    // cb.white_pawns = 0x80007F00;
-    // cb.white_to_move = false;
-    // cb.black_pawns = 0xFE000100000000;
-    // cb.white_to_move = true;
-    // cb.white_rooks = 0x800001;
-    // cb.white_to_move = false;
-    // cb.black_pawns = 0xFE000001000000;
-    // cb.white_to_move = true;
-
-    cb.print_chessboard();
-    // let x = cb.get_all_legal_white_moves(None).0;
-    // for a in x {
-    //     a.print_chessboard();
-    //     // println!("AA");
-    //     println!("{}", a.evaluate());
-    // }
-    let now = Instant::now();
-    // cb.perft(3);
-    let numOfPositions = cb.perft(4);
-    // let search = cb.minimax(DEPTH);
-    // for mov in search.iter() {
-    //     mov.0.print_chessboard();
-    //     println!("Eval: {}", mov.0.evaluate());
-    //     println!("Minimax eval: {}", mov.1);
-    // }
-
-    println!("------------------------------------");
-    // let search2 = search.iter().last().unwrap().0.minimax(3);
-
-    // for mov in search2.iter().rev() {
-    //     mov.0.print_chessboard();
-    //     println!("Eval: {}", mov.0.evaluate());
-    //     println!("Minimax eval: {}", mov.1);
-    // }
-
-    // for (k, v) in x.0 { 
-    //     k.print_chessboard();
-    //     println!("Positions: {}", v);
-    // }
-    // println!("Total positions: {}", x.1);
-    // println!("{:#?} \n\n", cb.perft(5));
-    println!("Number of positions at depth {}: {}", DEPTH, numOfPositions.1);
-    println!("Elapsed time to calculate to depth {}: {}ms", DEPTH, now.elapsed().as_millis());
-    // cb.perft(5);
-
-    // let white_move = cb.get_all_legal_white_moves();
-    // println!("Move 1 #: {}", white_move.0.len());
-    // for (i, mov) in white_move.1.iter().enumerate() {
-    //     println!("Move 2 #: {}", mov.len());
-    //     for m in mov {
-    //         // m.print_chessboard();
-    //     }
-
-
-    //     return;
-    // }
-
-    println!("std::mem::size_of::<ChessBoard>(): {}",std::mem::size_of::<ChessBoard>());
-
-    let mut last_positions: Vec<ChessBoard> = vec![cb];
-    
-    let (mut legal_moves, mut pseudo_legal_white_moves, mut pseudo_legal_black_moves): (Vec<ChessBoard>, Vec<Vec<ChessBoard>>, Vec<Vec<ChessBoard>>) = (vec![], vec![], vec![]);
-    // let mut pseudo_legal_opposite_moves;
-    let mut param: Option<&Vec<ChessBoard>>;
+#![allow(non_snake_case, clippy::needless_return)]
+
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chess_engine::{search, uci_loop, write_pgn, ChessBoard, Game, GameStatus, Move, PerftStats, SearchInfo, SearchLimits, STARTING_FEN};
+
+const USAGE: &str = "\
+usage: ChessEngine [command] [depth] [options]
+
+commands:
+  uci                       speak the Universal Chess Interface (the default)
+  perft <depth>             count the leaf nodes of the move tree
+  divide <depth>            perft, split up by the first move
+  search                    find the best move in the position
+  eval                      show the position and its static evaluation
+  play                      play a game against the engine on the terminal
+  bench                     search a fixed set of positions and report the speed
+
+options:
+  --fen <fen>               position to start from (default: the initial position)
+  --depth <plies>           search depth
+  --movetime <ms>           time to search for each move
+  --nodes <count>           number of nodes to search for each move
+  --color <white|black>     side the human plays in play (default: white)
+  --threads <count>         threads for perft and divide (default: one per core)
+  --stats                   perft: break the count of every depth down by kind of move";
+
+// positions searched by bench, from the usual perft suites
+const BENCH_POSITIONS: [&str; 6] = [
+    STARTING_FEN,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+const BENCH_DEPTH: u32 = 4;
+const DEFAULT_PERFT_DEPTH: u32 = 5;
+const DEFAULT_SEARCH_DEPTH: u32 = 6;
+
+struct Options {
+    command: String,
+    fen: String,
+    depth: Option<u32>,
+    movetime: Option<Duration>,
+    nodes: Option<u64>,
+    human_plays_white: bool,
+    stats: bool,
+    threads: usize,
+}
+
+impl Options {
+    fn parse(arguments: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            command: "uci".to_string(),
+            fen: STARTING_FEN.to_string(),
+            depth: None,
+            movetime: None,
+            nodes: None,
+            human_plays_white: true,
+            stats: false,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        };
+
+        let mut arguments = arguments.iter();
+        let mut command = None;
+        while let Some(argument) = arguments.next() {
+            let mut value = |name: &str| arguments.next().ok_or_else(|| format!("{} needs a value", name));
+            match argument.as_str() {
+                "--fen" => options.fen = value("--fen")?.clone(),
+                "--depth" => options.depth = Some(parse_number(value("--depth")?)?),
+                "--movetime" => options.movetime = Some(Duration::from_millis(parse_number(value("--movetime")?)?)),
+                "--stats" => options.stats = true,
+                "--threads" => options.threads = parse_number::<usize>(value("--threads")?)?.max(1),
+                "--nodes" => options.nodes = Some(parse_number(value("--nodes")?)?),
+                "--color" => {
+                    options.human_plays_white = match value("--color")?.as_str() {
+                        "white" | "w" => true,
+                        "black" | "b" => false,
+                        other => return Err(format!("unknown color '{}'", other)),
+                    }
+                }
+                _ if argument.starts_with("--") => return Err(format!("unknown option '{}'", argument)),
+                // a bare number after the command is the depth
+                _ if command.is_some() => options.depth = Some(parse_number(argument)?),
+                _ => command = Some(argument.clone()),
+            }
+        }
+
+        if let Some(command) = command {
+            options.command = command;
+        }
+        return Ok(options);
+    }
+
+    fn board(&self) -> Result<ChessBoard, String> {
+        return ChessBoard::from_fen(&self.fen).map_err(|error| format!("invalid FEN: {}", error));
+    }
+
+    fn search_limits(&self) -> SearchLimits {
+        let mut limits = SearchLimits { depth: self.depth, nodes: self.nodes, movetime: self.movetime };
+        if limits.depth.is_none() && limits.nodes.is_none() && limits.movetime.is_none() {
+            limits.depth = Some(DEFAULT_SEARCH_DEPTH);
+        }
+        return limits;
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    return text.parse().map_err(|_| format!("'{}' is not a valid number", text));
+}
+
+fn nodes_per_second(nodes: u64, elapsed: Duration) -> u64 {
+    return (nodes as f64 / elapsed.as_secs_f64().max(1e-9)) as u64;
+}
+
+// pv in SAN, starting from chessboard
+fn pv_to_san(chessboard: &ChessBoard, pv: &[Move]) -> String {
+    let mut chessboard = *chessboard;
+    let mut sans = vec![];
+    for mov in pv {
+        sans.push(chessboard.move_to_san(*mov));
+        chessboard.make_move(*mov);
+    }
+    return sans.join(" ");
+}
+
+// search scores are from the side to move's point of view
+fn white_score(chessboard: &ChessBoard, score: f32) -> f32 {
+    return if chessboard.white_to_move { score } else { -score };
+}
+
+fn print_search_info(chessboard: &ChessBoard, info: &SearchInfo) {
+    let score = match info.mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("{:+.2}", white_score(chessboard, info.score)),
+    };
+    println!(
+        "depth {:>2}  score {:>8}  nodes {:>10}  time {:>6}ms  pv {}",
+        info.depth,
+        score,
+        info.nodes,
+        info.time.as_millis(),
+        pv_to_san(chessboard, &info.pv)
+    );
+}
+
+fn perft(options: &Options) -> Result<(), String> {
+    let chessboard = options.board()?;
+    let depth = options.depth.unwrap_or(DEFAULT_PERFT_DEPTH);
+
+    if options.stats {
+        println!("Depth{}", PerftStats::HEADER);
+        for depth in 1..=depth {
+            println!("{:>5}{}", depth, chessboard.parallel_perft_stats(depth as u64, options.threads));
+        }
+        return Ok(());
+    }
+
+    // build the slider attack tables now, so they don't count towards the time
+    chessboard.legal_moves(None);
+
     let now = Instant::now();
+    let nodes = chessboard.parallel_perft(depth as u64, options.threads);
+    let elapsed = now.elapsed();
+
+    println!("Nodes at depth {}: {}", depth, nodes);
+    println!("Time: {}ms ({} nodes/s, threads: {})", elapsed.as_millis(), nodes_per_second(nodes, elapsed), options.threads);
+    return Ok(());
+}
+
+fn divide(options: &Options) -> Result<(), String> {
+    let chessboard = options.board()?;
+    let depth = options.depth.unwrap_or(DEFAULT_PERFT_DEPTH).max(1);
+
+    let divide = chessboard.parallel_divide(depth as u64, options.threads);
+    for (mov, nodes) in &divide {
+        println!("{}: {}", mov, nodes);
+    }
+    println!();
+    println!("Nodes searched: {}", divide.iter().map(|(_, nodes)| nodes).sum::<u64>());
+    return Ok(());
+}
+
+fn search_position(options: &Options) -> Result<(), String> {
+    let chessboard = options.board()?;
+
+    let stop = AtomicBool::new(false);
+    let result = search(&Game::from_board(chessboard), options.search_limits(), &stop, |info| print_search_info(&chessboard, info));
+    match result.and_then(|result| result.pv.first().copied()) {
+        Some(mov) => println!("Best move: {} ({})", chessboard.move_to_san(mov), mov),
+        None => println!("No legal moves: {}", chessboard.status().result()),
+    }
+    return Ok(());
+}
+
+fn eval(options: &Options) -> Result<(), String> {
+    let chessboard = options.board()?;
+
+    chessboard.print_chessboard();
+    println!("FEN: {}", chessboard.to_fen());
+    println!("Evaluation: {:+.2} (from white's point of view)", chessboard.evaluate());
+    println!("Status: {:?}", chessboard.status());
+    return Ok(());
+}
+
+// Claimable draws leave it to the players to stop, everything else ends the game
+fn ends_the_game(status: GameStatus) -> bool {
+    return !matches!(status, GameStatus::Ongoing | GameStatus::ThreefoldRepetition | GameStatus::FiftyMoveRule);
+}
+
+fn play(options: &Options) -> Result<(), String> {
+    let mut game = Game::from_board(options.board()?);
+    let limits = options.search_limits();
+    let stop = AtomicBool::new(false);
+    let mut lines = io::stdin().lock().lines();
+    let mut claimed = false;
+
+    println!("Enter moves in SAN (Nf3) or coordinates (g1f3), 'undo' to take back your last move, 'draw' to claim a draw, 'quit' to stop.");
+    while !ends_the_game(game.status()) {
+        let chessboard = *game.board();
+        chessboard.print_chessboard();
+
+        // ENGINE MOVE
+        if chessboard.white_to_move != options.human_plays_white {
+            let result = search(&game, limits, &stop, |_| {}).expect("the game isn't over, so there is a legal move");
+            let mov = result.pv[0];
+            println!("Engine plays {} (score {:+.2})", chessboard.move_to_san(mov), white_score(&chessboard, result.score));
+            game.play(mov).expect("search only returns legal moves");
+            continue;
+        }
+
+        // HUMAN MOVE
+        let status = game.status();
+        if status.is_over() {
+            println!("You can claim a draw ({:?}) with 'draw' or play on.", status);
+        }
+        print!("Your move: ");
+        io::stdout().flush().map_err(|error| error.to_string())?;
+        let Some(Ok(line)) = lines.next() else { break };
+        let text = line.trim();
+        match text {
+            "" => continue,
+            "quit" => break,
+            "draw" if status.is_over() => {
+                claimed = true;
+                break;
+            }
+            "draw" => {
+                println!("No draw to claim in this position");
+                continue;
+            }
+            "undo" => {
+                // the engine's reply and the move before it
+                game.undo();
+                if game.board().white_to_move != options.human_plays_white {
+                    game.undo();
+                }
+                continue;
+            }
+            _ => {}
+        }
+        match chessboard.parse_uci_move(text).or_else(|_| chessboard.parse_san(text)) {
+            Ok(mov) => game.play(mov).expect("parsed moves are legal"),
+            Err(error) => println!("{}", error),
+        }
+    }
+
+    // a draw nobody claimed leaves the game unfinished
+    let status = game.status();
+    let result = if claimed || ends_the_game(status) { status.result() } else { "*" };
+    game.board().print_chessboard();
+    println!("{:?}", status);
+    println!();
+    print!("{}", write_pgn(game.starting_position(), game.moves(), &[], None, result));
+    return Ok(());
+}
+
+fn bench(options: &Options) -> Result<(), String> {
+    let limits = SearchLimits { depth: Some(options.depth.unwrap_or(BENCH_DEPTH)), ..Default::default() };
+    let stop = AtomicBool::new(false);
+
+    let (mut total_nodes, mut total_time) = (0, Duration::ZERO);
+    for fen in BENCH_POSITIONS {
+        let chessboard = ChessBoard::from_fen(fen).expect("bench positions are valid");
+        let now = Instant::now();
+        let nodes = search(&Game::from_board(chessboard), limits, &stop, |_| {}).map_or(0, |result| result.nodes);
+        let elapsed = now.elapsed();
+
+        println!("{:>10} nodes {:>6}ms  {}", nodes, elapsed.as_millis(), fen);
+        total_nodes += nodes;
+        total_time += elapsed;
+    }
+
+    println!("Nodes searched: {}", total_nodes);
+    println!("Time: {}ms ({} nodes/s)", total_time.as_millis(), nodes_per_second(total_nodes, total_time));
+    return Ok(());
+}
 
-    let mut first_moves: HashMap<ChessBoard, u64> = HashMap::new();
-    // for ply_num in 1..6 {
-    //     println!("Ply #{}", ply_num);
-    //     let mut positions: Vec<ChessBoard> = vec![];
-    //     println!("Positions len {}", positions.len());
-    //     for (i,pos) in last_positions.iter().enumerate() {
-    //         if pos.white_to_move {
-
-    //             if pseudo_legal_white_moves.is_empty(){
-    //                 param = None;
-    //             } else {
-    //                 param = None;
-    //                 // param = Some(&pseudo_legal_white_moves[i]);
-    //                 // println!("{:?}", param);
-    //             }
-
-    //             (legal_moves, pseudo_legal_opposite_moves) = pos.get_all_legal_white_moves(param);
-    //             positions.extend(legal_moves);
-    //             pseudo_legal_black_moves.extend(pseudo_legal_opposite_moves);
-    //             pseudo_legal_white_moves.clear();
-    //             // println!("Move #{}: {} possible moves", ply_num, positions.len())
-    //         } else {
-    //             if pseudo_legal_black_moves.is_empty(){
-    //                 param = None;
-    //             } else {
-    //                 param = None;
-    //                 // param = Some(&pseudo_legal_black_moves[i]);
-    //             }
-    //             (legal_moves, pseudo_legal_opposite_moves) = pos.get_all_legal_black_moves(param);
-    //             positions.extend(legal_moves);
-    //             pseudo_legal_white_moves.extend(pseudo_legal_opposite_moves);
-    //             pseudo_legal_black_moves.clear();
-    //             // opposite_pseudo_legal_positions.extend(pseudo_legal_opposite_moves);
-    //             // println!("Move #{}: {} possible moves", ply_num, positions.len())  
-    //         }
-
-    //     }
-
-    //     // if ply_num == 1 {
-    //     //     for pos in &positions {
-    //     //         first_moves.insert(*pos, 0);
-    //     //     }
-    //     // } else {
-    //     //     for  in  {
-                
-    //     //     }
-    //     // }
-
-    //     // let mut counter = 0;
-    //     // if ply_num == 5 {
-    //     //     for pos in &positions {
-    //     //         if pos.black_queens & 0x800000000000000 == 0 || pos.black_bishops & 0x2000000000000000 == 0 || pos.black_bishops & 0x400000000000000 == 0
-    //     //         || pos.black_knights & 0x200000000000000 == 0 || pos.black_knights & 0x4000000000000000 == 0   {
-    //     //             pos.print_chessboard();
-    //     //         }
-    //     //     }
-    //     //     // println!("NUMBER OF CHECKMATES: {}", counter);
-    //     // }
-
-    //     println!("Possible positions after move #{}: {} positions", ply_num, positions.len());
-    //     println!("pseudo_legal_white_moves len #{}: {}", ply_num, pseudo_legal_white_moves.len());
-    //     println!("pseudo_legal_black_moves len #{}: {}", ply_num, pseudo_legal_black_moves.len());
-
-        
-    //     last_positions = positions;
-    //     // positions.clear();
-        
-    // }
-    
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let result = Options::parse(&arguments).and_then(|options| match options.command.as_str() {
+        "uci" => {
+            uci_loop();
+            return Ok(());
+        }
+        "perft" => perft(&options),
+        "divide" => divide(&options),
+        "search" => search_position(&options),
+        "eval" => eval(&options),
+        "play" => play(&options),
+        "bench" => bench(&options),
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        other => Err(format!("unknown command '{}'", other)),
+    });
 
+    match result {
+        Ok(()) => return ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            eprintln!();
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    }
 }
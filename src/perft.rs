@@ -0,0 +1,184 @@
+// Perft statistics
+//
+// Besides the node count, classify the moves leading to the leaf nodes the way
+// the published perft tables do (https://www.chessprogramming.org/Perft_Results):
+// captures (en passant included), en passant captures, castles, promotions,
+// checks, discovered checks, double checks and checkmates. A discrepancy in one
+// of the columns points at the rule that is broken.
+//
+// A check is discovered when it's given by a piece other than the one that
+// moved (the rook counts as moved when castling). Like in the published
+// tables, double checks are only counted as double checks.
+//
+// The parallel versions hand out the root moves to a number of threads, each
+// thread takes the next move nobody has started on until all are done, so
+// threads that draw small subtrees simply take more of them.
+
+use std::fmt;
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::chess_move::Move;
+use crate::chessboard::chessboard::ChessBoard;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passant: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub discovered_checks: u64,
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+impl PerftStats {
+    pub const HEADER: &'static str = "       Nodes    Captures        E.p.     Castles  Promotions      Checks  Disc. checks  Double checks  Checkmates";
+}
+
+impl AddAssign for PerftStats {
+    fn add_assign(&mut self, other: Self) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.en_passant += other.en_passant;
+        self.castles += other.castles;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+        self.discovered_checks += other.discovered_checks;
+        self.double_checks += other.double_checks;
+        self.checkmates += other.checkmates;
+    }
+}
+
+// one row of the table, lined up with HEADER
+impl fmt::Display for PerftStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:>12}{:>12}{:>12}{:>12}{:>12}{:>12}{:>14}{:>15}{:>12}",
+            self.nodes,
+            self.captures,
+            self.en_passant,
+            self.castles,
+            self.promotions,
+            self.checks,
+            self.discovered_checks,
+            self.double_checks,
+            self.checkmates
+        )
+    }
+}
+
+impl ChessBoard {
+    pub fn perft_stats(&self, depth: u64) -> PerftStats {
+        let mut stats = PerftStats::default();
+        if depth == 0 {
+            stats.nodes = 1;
+            return stats;
+        }
+
+        let mut chessboard = *self;
+        chessboard._perft_stats(depth, &mut stats);
+        return stats;
+    }
+
+    // count runs on the position after every root move, results in move generation order
+    fn split_root_moves<T: Send>(&self, threads: usize, count: impl Fn(&mut ChessBoard) -> T + Sync) -> Vec<(Move, T)> {
+        let moves = self.legal_moves(None);
+        let next = AtomicUsize::new(0);
+
+        let mut results: Vec<(usize, T)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.clamp(1, moves.len().max(1)))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(&mov) = moves.get(i) else { break };
+                            let mut chessboard = *self;
+                            chessboard.make_move(mov);
+                            results.push((i, count(&mut chessboard)));
+                        }
+                        return results;
+                    })
+                })
+                .collect();
+            return workers.into_iter().flat_map(|worker| worker.join().expect("perft thread panicked")).collect();
+        });
+
+        results.sort_by_key(|(i, _)| *i);
+        return results.into_iter().map(|(i, result)| (moves[i], result)).collect();
+    }
+
+    pub fn parallel_perft(&self, depth: u64, threads: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        return self.split_root_moves(threads, |chessboard| chessboard._perft(depth - 1)).iter().map(|(_, nodes)| nodes).sum();
+    }
+
+    // same result as divide()
+    pub fn parallel_divide(&self, depth: u64, threads: usize) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return vec![];
+        }
+
+        let mut result = self.split_root_moves(threads, |chessboard| chessboard._perft(depth - 1));
+        result.sort_by_key(|(mov, _)| mov.to_uci());
+        return result;
+    }
+
+    pub fn parallel_perft_stats(&self, depth: u64, threads: usize) -> PerftStats {
+        // the root moves themselves are the leaves
+        if depth <= 1 {
+            return self.perft_stats(depth);
+        }
+
+        let mut stats = PerftStats::default();
+        for (_, child_stats) in self.split_root_moves(threads, |chessboard| chessboard.perft_stats(depth - 1)) {
+            stats += child_stats;
+        }
+        return stats;
+    }
+
+    fn _perft_stats(&mut self, depth: u64, stats: &mut PerftStats) {
+        for mov in self.legal_moves(None) {
+            let undo = self.make_move(mov);
+
+            if depth > 1 {
+                self._perft_stats(depth - 1, stats);
+                self.unmake_move(mov, undo);
+                continue;
+            }
+
+            // LEAF
+            stats.nodes += 1;
+            stats.captures += mov.is_capture() as u64;
+            stats.en_passant += mov.is_en_passant() as u64;
+            stats.castles += mov.is_castle() as u64;
+            stats.promotions += mov.is_promotion() as u64;
+
+            let checkers = self.check_info().checkers;
+            if checkers > 0 {
+                // squares the move put a piece on, king to g1 moves the rook to f1
+                let mut moved = mov.get_to_square();
+                if mov.is_castle() {
+                    moved |= if mov.to > mov.from { moved >> 1 } else { moved << 1 };
+                }
+
+                stats.checks += 1;
+                if checkers.count_ones() > 1 {
+                    stats.double_checks += 1;
+                } else if checkers & moved == 0 {
+                    stats.discovered_checks += 1;
+                }
+                stats.checkmates += self.legal_moves(None).is_empty() as u64;
+            }
+
+            self.unmake_move(mov, undo);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+// Portable Game Notation
+//
+// [Event "Casual game"]
+// [White "..."]
+// 1. e4 e5 2. Nf3 {comment} Nc6 $1 (2... d6 3. d4) 3. Bb5 a6 1-0
+//
+// Tag pairs, then the movetext: SAN moves with optional move numbers, comments
+// in braces or after a semicolon, numeric annotation glyphs ($1), variations in
+// parentheses (which may nest) and the result. The main line is replayed into
+// the game, variations are played through to check their moves and then
+// dropped, comments and NAGs are skipped.
+//
+// Written games start with the seven tag roster (Event, Site, Date, Round,
+// White, Black, Result), followed by SetUp and FEN when the game doesn't start
+// from the initial position and then any other tags.
+
+use std::fmt;
+
+use crate::chess_move::Move;
+use crate::chessboard::chessboard::ChessBoard;
+use crate::fen::FenError;
+use crate::game::Game;
+use crate::san::SanError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PgnError {
+    InvalidTag(String),
+    UnterminatedComment,
+    UnbalancedVariation,
+    InvalidFen(FenError),
+    // ply counts from 1 at the first move of the game
+    IllegalMove { ply: usize, text: String, reason: SanError },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PgnError::InvalidTag(tag) => write!(f, "invalid tag pair '{}'", tag),
+            PgnError::UnterminatedComment => write!(f, "comment is never closed"),
+            PgnError::UnbalancedVariation => write!(f, "unbalanced parentheses around a variation"),
+            PgnError::InvalidFen(error) => write!(f, "invalid FEN tag: {}", error),
+            PgnError::IllegalMove { ply, reason, .. } => write!(f, "ply {}: {}", ply, reason),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    // in the order they appear
+    pub tags: Vec<(String, String)>,
+    // starting position (FEN tag or the initial position) and the main line
+    pub game: Game,
+    // "1-0", "0-1", "1/2-1/2" or "*"
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        return self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str());
+    }
+
+    pub fn to_pgn(&self) -> String {
+        return write_pgn(self.game.starting_position(), self.game.moves(), &self.tags, None, &self.result);
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Tag(String, String),
+    OpenVariation,
+    CloseVariation,
+    Result(String),
+    // a SAN move, with move numbers already stripped off
+    Move(String),
+}
+
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+// (tag, value when unknown), Result always comes from the result itself
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+// movetext lines are wrapped before this many characters
+const LINE_LENGTH: usize = 80;
+
+// [Name "value"], value may contain \" and \\
+fn parse_tag(text: &str) -> Result<Token, PgnError> {
+    let invalid = || PgnError::InvalidTag(text.to_string());
+
+    let inner = text.strip_prefix('[').and_then(|inner| inner.strip_suffix(']')).ok_or_else(invalid)?.trim();
+    let (name, quoted) = inner.split_once(char::is_whitespace).ok_or_else(invalid)?;
+    let quoted = quoted.trim();
+    let value = quoted.strip_prefix('"').and_then(|value| value.strip_suffix('"')).ok_or_else(invalid)?;
+    if name.is_empty() || !name.chars().all(|character| character.is_ascii_alphanumeric() || character == '_') {
+        return Err(invalid());
+    }
+
+    let mut unescaped = String::new();
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            unescaped.push(characters.next().ok_or_else(invalid)?);
+        } else {
+            unescaped.push(character);
+        }
+    }
+    return Ok(Token::Tag(name.to_string(), unescaped));
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, PgnError> {
+    let mut tokens = vec![];
+    let mut characters = text.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(&character) = characters.peek() {
+        // a % in the first column escapes the rest of the line
+        if line_start && character == '%' {
+            characters.find(|character| *character == '\n');
+            continue;
+        }
+        line_start = character == '\n';
+
+        match character {
+            '[' => {
+                // read up to the closing bracket that isn't part of the quoted value
+                let mut tag = String::new();
+                let (mut quoted, mut escaped) = (false, false);
+                loop {
+                    let character = characters.next().ok_or_else(|| PgnError::InvalidTag(tag.clone()))?;
+                    tag.push(character);
+                    match character {
+                        _ if escaped => escaped = false,
+                        '\\' if quoted => escaped = true,
+                        '"' => quoted = !quoted,
+                        ']' if !quoted => break,
+                        _ => {}
+                    }
+                }
+                tokens.push(parse_tag(&tag)?);
+            }
+            '{' => {
+                // braces don't nest
+                characters.find(|character| *character == '}').ok_or(PgnError::UnterminatedComment)?;
+            }
+            ';' => {
+                characters.find(|character| *character == '\n');
+                line_start = true;
+            }
+            '(' => {
+                characters.next();
+                tokens.push(Token::OpenVariation);
+            }
+            ')' => {
+                characters.next();
+                tokens.push(Token::CloseVariation);
+            }
+            '$' => {
+                characters.next();
+                while characters.next_if(|character| character.is_ascii_digit()).is_some() {}
+            }
+            _ if character.is_whitespace() => {
+                characters.next();
+            }
+            _ => {
+                let mut symbol = String::new();
+                while let Some(character) = characters.next_if(|character| !character.is_whitespace() && !"[]{};()$".contains(*character)) {
+                    symbol.push(character);
+                }
+
+                if RESULTS.contains(&symbol.as_str()) {
+                    tokens.push(Token::Result(symbol));
+                    continue;
+                }
+
+                // move numbers: "12." or "12..." possibly glued to the move
+                let digits = symbol.chars().take_while(|character| character.is_ascii_digit()).count();
+                if digits > 0 && symbol[digits..].starts_with('.') {
+                    symbol = symbol[digits..].trim_start_matches('.').to_string();
+                }
+                // a lone "." or an annotation like "!?" written apart from its move
+                if symbol.is_empty() || symbol.chars().all(|character| "!?.".contains(character)) {
+                    continue;
+                }
+                tokens.push(Token::Move(symbol));
+            }
+        }
+    }
+
+    return Ok(tokens);
+}
+
+// Every game in text, in order
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = vec![];
+
+    let mut tags: Vec<(String, String)> = vec![];
+    // moves and parentheses, results inside variations left out
+    let mut movetext: Vec<Token> = vec![];
+    let mut depth = 0;
+
+    for token in tokenize(text)? {
+        match token {
+            Token::Tag(name, value) => {
+                // tags after movetext without a result start the next game
+                if !movetext.is_empty() {
+                    games.push(replay(std::mem::take(&mut tags), std::mem::take(&mut movetext), "*".to_string())?);
+                }
+                tags.push((name, value));
+            }
+            Token::OpenVariation => {
+                depth += 1;
+                movetext.push(token);
+            }
+            Token::CloseVariation => {
+                if depth == 0 {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                depth -= 1;
+                movetext.push(token);
+            }
+            Token::Move(_) => movetext.push(token),
+            Token::Result(result) => {
+                // a result inside a variation only ends the variation
+                if depth > 0 {
+                    continue;
+                }
+                games.push(replay(std::mem::take(&mut tags), std::mem::take(&mut movetext), result)?);
+            }
+        }
+    }
+
+    if depth > 0 {
+        return Err(PgnError::UnbalancedVariation);
+    }
+    if !movetext.is_empty() || !tags.is_empty() {
+        games.push(replay(tags, movetext, "*".to_string())?);
+    }
+
+    return Ok(games);
+}
+
+// Plays the main line into the game and checks every variation on a copy of
+// the board. A variation replaces the move before it, so it starts from the
+// position that move was played in.
+fn replay(tags: Vec<(String, String)>, movetext: Vec<Token>, result: String) -> Result<PgnGame, PgnError> {
+    let mut game = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => Game::from_fen(fen).map_err(PgnError::InvalidFen)?,
+        None => Game::new(),
+    };
+
+    // the line being read: its position, the ply of its last move and the
+    // position before that move (where a variation would start)
+    let mut board = *game.board();
+    let mut ply = 0;
+    let mut before_last_move = board;
+    // the lines the open variations branched off from
+    let mut stack: Vec<(ChessBoard, usize, ChessBoard)> = vec![];
+
+    for token in movetext {
+        match token {
+            Token::OpenVariation => {
+                stack.push((board, ply, before_last_move));
+                board = before_last_move;
+                ply = ply.saturating_sub(1);
+            }
+            Token::CloseVariation => {
+                (board, ply, before_last_move) = stack.pop().expect("parse_pgn balances the parentheses");
+            }
+            Token::Move(san) => {
+                let mov = board.parse_san(&san).map_err(|reason| PgnError::IllegalMove { ply: ply + 1, text: san.clone(), reason })?;
+                before_last_move = board;
+                board.make_move(mov);
+                ply += 1;
+                if stack.is_empty() {
+                    game.play(mov).expect("parse_san only returns legal moves");
+                }
+            }
+            Token::Tag(..) | Token::Result(_) => unreachable!("parse_pgn only passes on moves and parentheses"),
+        }
+    }
+
+    return Ok(PgnGame { tags, game, result });
+}
+
+fn escape_tag_value(value: &str) -> String {
+    return value.replace('\\', "\\\\").replace('"', "\\\"");
+}
+
+// tags: values for the roster and any extra tags, SetUp, FEN and Result are
+// derived from the other arguments instead. evals: one score per move (in
+// pawns, from white's point of view), written as a comment after the move.
+pub fn write_pgn(starting_position: &ChessBoard, moves: &[Move], tags: &[(String, String)], evals: Option<&[f32]>, result: &str) -> String {
+    let mut pgn = String::new();
+    let mut write_tag = |name: &str, value: &str| pgn.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)));
+
+    // TAGS
+    for (name, unknown) in SEVEN_TAG_ROSTER {
+        let value = match name {
+            "Result" => result,
+            _ => tags.iter().find(|(tag, _)| tag == name).map_or(unknown, |(_, value)| value.as_str()),
+        };
+        write_tag(name, value);
+    }
+    if *starting_position != ChessBoard::new() {
+        write_tag("SetUp", "1");
+        write_tag("FEN", &starting_position.to_fen());
+    }
+    for (name, value) in tags {
+        let derived = ["SetUp", "FEN"].contains(&name.as_str()) || SEVEN_TAG_ROSTER.iter().any(|(roster, _)| roster == name);
+        if !derived {
+            write_tag(name, value);
+        }
+    }
+    pgn.push('\n');
+
+    // MOVETEXT
+    let mut tokens: Vec<String> = vec![];
+    let mut chessboard = *starting_position;
+    // black moves need their number at the start and after a comment
+    let mut needs_move_number = true;
+    for (i, mov) in moves.iter().enumerate() {
+        // keep the move number on the same line as its move
+        let mut token = String::new();
+        if chessboard.white_to_move {
+            token = format!("{}. ", chessboard.fullmove_number);
+        } else if needs_move_number {
+            token = format!("{}... ", chessboard.fullmove_number);
+        }
+        token.push_str(&chessboard.move_to_san(*mov));
+        tokens.push(token);
+        needs_move_number = false;
+
+        if let Some(eval) = evals.and_then(|evals| evals.get(i)) {
+            tokens.push(format!("{{[%eval {:.2}]}}", eval));
+            needs_move_number = true;
+        }
+        chessboard.make_move(*mov);
+    }
+    tokens.push(result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() >= LINE_LENGTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+
+    return pgn;
+}
@@ -0,0 +1,195 @@
+// Standard Algebraic Notation
+//
+// Nbd7, exd8=N+, O-O-O, Qh4#
+// piece letter (none for pawns), file and/or rank of the starting square when
+// another piece of the same kind could reach the same square, x for captures,
+// destination square, =piece for promotions, + for check and # for mate.
+// A SAN string only means something relative to the position it is played in.
+
+use std::fmt;
+
+use crate::chess_move::{Move, Piece};
+use crate::chessboard::chessboard::{ChessBoard, EnumSquare};
+use crate::fen::{piece_from_char, piece_to_char};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SanError {
+    Empty,
+    InvalidSyntax(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SanError::Empty => write!(f, "empty move"),
+            SanError::InvalidSyntax(san) => write!(f, "'{}' is not a move in standard algebraic notation", san),
+            SanError::IllegalMove(san) => write!(f, "'{}' is not a legal move in this position", san),
+            SanError::AmbiguousMove(san) => write!(f, "'{}' matches more than one legal move", san),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+impl ChessBoard {
+    // piece of the side to move standing on square
+    fn get_own_piece(&self, square: u64) -> Option<Piece> {
+        if self.white_to_move {
+            return self.get_white_piece(square);
+        } else {
+            return self.get_black_piece(square);
+        }
+    }
+
+    // mov has to be legal in this position
+    pub fn move_to_san(&self, mov: Move) -> String {
+        let mut san = String::new();
+
+        if mov.is_castle() {
+            san.push_str(if mov.to > mov.from { "O-O" } else { "O-O-O" });
+        } else {
+            let piece = self.get_own_piece(mov.get_from_square()).expect("no piece of the side to move on the starting square");
+            let from_name = EnumSquare::ALL[mov.from as usize].to_string();
+
+            if piece == Piece::Pawn {
+                // pawn captures always name the file the pawn came from
+                if mov.is_capture() {
+                    san.push_str(&from_name[..1]);
+                }
+            } else {
+                san.push(piece_to_char(piece, true));
+
+                // other pieces of the same kind that can go to the same square
+                let rivals: Vec<Move> = self
+                    .legal_moves(None)
+                    .into_iter()
+                    .filter(|other| other.to == mov.to && other.from != mov.from && self.get_own_piece(other.get_from_square()) == Some(piece))
+                    .collect();
+                if !rivals.is_empty() {
+                    let shares_file = rivals.iter().any(|other| other.from % 8 == mov.from % 8);
+                    let shares_rank = rivals.iter().any(|other| other.from / 8 == mov.from / 8);
+                    if !shares_file {
+                        san.push_str(&from_name[..1]);
+                    } else if !shares_rank {
+                        san.push_str(&from_name[1..]);
+                    } else {
+                        san.push_str(&from_name);
+                    }
+                }
+            }
+
+            if mov.is_capture() {
+                san.push('x');
+            }
+            san.push_str(&EnumSquare::ALL[mov.to as usize].to_string());
+
+            if let Some(promotion) = mov.promotion {
+                san.push('=');
+                san.push(piece_to_char(promotion, true));
+            }
+        }
+
+        let mut chessboard = *self;
+        chessboard.make_move(mov);
+        if chessboard.is_checkmate() {
+            san.push('#');
+        } else if chessboard.is_in_check() {
+            san.push('+');
+        }
+
+        return san;
+    }
+
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let invalid = || SanError::InvalidSyntax(san.to_string());
+
+        // check marks and annotations like !? carry no information about the move
+        let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        if text.is_empty() {
+            return Err(SanError::Empty);
+        }
+
+        let legal_moves = self.legal_moves(None);
+
+        // CASTLING (also accept zeros, as some programs write them)
+        let castle = match text {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some(short) = castle {
+            return legal_moves
+                .into_iter()
+                .find(|mov| mov.is_castle() && (mov.to > mov.from) == short)
+                .ok_or_else(|| SanError::IllegalMove(san.to_string()));
+        }
+
+        // PIECE
+        let (piece, mut rest) = match text.chars().next() {
+            Some(character @ ('N' | 'B' | 'R' | 'Q' | 'K')) => (piece_from_char(character).ok_or_else(invalid)?, &text[1..]),
+            _ => (Piece::Pawn, text),
+        };
+
+        // PROMOTION, e8=Q or e8Q
+        let mut promotion = None;
+        if piece == Piece::Pawn {
+            let promotion_text = if let Some(index) = rest.find('=') {
+                let promotion_text = &rest[index + 1..];
+                rest = &rest[..index];
+                Some(promotion_text)
+            } else if rest.ends_with(['N', 'B', 'R', 'Q']) {
+                let index = rest.len() - 1;
+                let promotion_text = &rest[index..];
+                rest = &rest[..index];
+                Some(promotion_text)
+            } else {
+                None
+            };
+
+            if let Some(promotion_text) = promotion_text {
+                let mut characters = promotion_text.chars();
+                promotion = match (characters.next().and_then(piece_from_char), characters.next()) {
+                    (Some(piece @ (Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen)), None) => Some(piece),
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+
+        // DESTINATION
+        if rest.len() < 2 || !rest.is_char_boundary(rest.len() - 2) {
+            return Err(invalid());
+        }
+        let to = EnumSquare::from_name(&rest[rest.len() - 2..]).ok_or_else(invalid)?.index();
+        rest = &rest[..rest.len() - 2];
+
+        // CAPTURE MARK AND DISAMBIGUATION
+        let is_capture = rest.ends_with(['x', ':']);
+        rest = rest.strip_suffix(['x', ':']).unwrap_or(rest);
+        let (mut from_file, mut from_rank) = (None, None);
+        for character in rest.chars() {
+            match character {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => from_file = Some(character as u8 - b'a'),
+                '1'..='8' if from_rank.is_none() => from_rank = Some(character as u8 - b'1'),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let mut candidates = legal_moves.into_iter().filter(|mov| {
+            !mov.is_castle()
+                && mov.to == to
+                && mov.promotion == promotion
+                && mov.is_capture() == is_capture
+                && self.get_own_piece(mov.get_from_square()) == Some(piece)
+                && from_file.is_none_or(|file| mov.from % 8 == file)
+                && from_rank.is_none_or(|rank| mov.from / 8 == rank)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(mov), None) => return Ok(mov),
+            (None, _) => return Err(SanError::IllegalMove(san.to_string())),
+            (Some(_), Some(_)) => return Err(SanError::AmbiguousMove(san.to_string())),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Search
+//
+// Iterative deepening negamax with alpha-beta pruning and a capture-only
+// quiescence search at the leaves. Scores are in pawns from the point of view
+// of the side to move (evaluate() scores from white's point of view), a side
+// that is mated at ply n scores -(MATE_SCORE - n). A position that already
+// occurred in the game or earlier in the line scores as a draw, since the side
+// that is worse off can repeat it again.
+//
+// The search stops when the depth, node or time limit is reached or when the
+// stop flag is raised from another thread. The node limit is checked on every
+// node, the clock and the flag every POLL_INTERVAL nodes. An interrupted
+// iteration is thrown away, except for the first one: its best root move so far
+// is still played, so there is always a move to play.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::chess_move::Move;
+use crate::chessboard::chessboard::{ChessBoard, MATE_SCORE};
+use crate::game::Game;
+
+const MAX_DEPTH: u32 = 64;
+
+// scores further from 0 than this are mates
+const MATE_THRESHOLD: f32 = MATE_SCORE - MAX_DEPTH as f32 * 2.0;
+
+// nodes between looking at the clock and the stop flag, well under a millisecond
+const POLL_INTERVAL: u32 = 256;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: f32,
+    pub nodes: u64,
+    pub time: Duration,
+    // principal variation, best move first
+    pub pv: Vec<Move>,
+}
+
+impl SearchInfo {
+    // Moves until mate (negative when the side to move gets mated), None if the score isn't a mate
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE_THRESHOLD {
+            return None;
+        }
+        let plies = (MATE_SCORE - self.score.abs()).round() as i32;
+        let moves = (plies + 1) / 2;
+        return Some(if self.score > 0.0 { moves } else { -moves });
+    }
+}
+
+struct Searcher<'a> {
+    limits: SearchLimits,
+    stop: &'a AtomicBool,
+    start: Instant,
+    nodes: u64,
+    // nodes left until the clock and the stop flag are looked at again
+    until_poll: u32,
+    aborted: bool,
+    // best line from the previous iteration, searched first
+    previous_pv: Vec<Move>,
+    // Zobrist keys of the game so far and the line being searched, the current position last
+    position_keys: Vec<u64>,
+}
+
+impl Searcher<'_> {
+    // Counts a node, true once the search has to stop
+    fn count_node(&mut self) -> bool {
+        if self.aborted {
+            return true;
+        }
+        self.nodes += 1;
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.aborted = true;
+        }
+
+        self.until_poll -= 1;
+        if self.until_poll == 0 {
+            self.until_poll = POLL_INTERVAL;
+            let out_of_time = self.limits.movetime.is_some_and(|movetime| self.start.elapsed() >= movetime);
+            self.aborted |= out_of_time || self.stop.load(Ordering::Relaxed);
+        }
+        return self.aborted;
+    }
+
+    // the current position occurred before since the last capture or pawn move
+    fn is_repetition(&self, chessboard: &ChessBoard) -> bool {
+        let reversible = (chessboard.halfmove_clock as usize).min(self.position_keys.len() - 1);
+        return self.position_keys.iter().rev().take(reversible + 1).skip(2).step_by(2).any(|key| *key == chessboard.zobrist_key);
+    }
+
+    fn evaluate(chessboard: &ChessBoard) -> f32 {
+        let evaluation = chessboard.evaluate();
+        return if chessboard.white_to_move { evaluation } else { -evaluation };
+    }
+
+    // previous best move first, then captures, then everything else
+    fn order_moves(&self, moves: &mut [Move], ply: usize) {
+        let pv_move = self.previous_pv.get(ply).copied();
+        moves.sort_by_key(|mov| {
+            if Some(*mov) == pv_move {
+                0
+            } else if mov.is_capture() || mov.is_promotion() {
+                1
+            } else {
+                2
+            }
+        });
+    }
+
+    fn quiescence(&mut self, chessboard: &mut ChessBoard, mut alpha: f32, beta: f32) -> f32 {
+        if self.count_node() {
+            return 0.0;
+        }
+
+        let stand_pat = Self::evaluate(chessboard);
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        for mov in chessboard.legal_moves(None).into_iter().filter(|mov| mov.is_capture()) {
+            let undo = chessboard.make_move(mov);
+            let score = -self.quiescence(chessboard, -beta, -alpha);
+            chessboard.unmake_move(mov, undo);
+
+            if self.aborted {
+                return 0.0;
+            }
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        return alpha;
+    }
+
+    fn negamax(&mut self, chessboard: &mut ChessBoard, depth: u32, ply: usize, mut alpha: f32, beta: f32, pv: &mut Vec<Move>) -> f32 {
+        pv.clear();
+
+        let mut moves = chessboard.legal_moves(None);
+        let is_draw = ply > 0 && (chessboard.is_insufficient_material() || chessboard.is_fifty_move_draw() || self.is_repetition(chessboard));
+        // quiescence counts the other leaves itself
+        if depth == 0 && !moves.is_empty() && !is_draw {
+            return self.quiescence(chessboard, alpha, beta);
+        }
+        if self.count_node() {
+            return 0.0;
+        }
+
+        if moves.is_empty() {
+            return if chessboard.is_in_check() { -(MATE_SCORE - ply as f32) } else { 0.0 };
+        }
+        if is_draw {
+            return 0.0;
+        }
+
+        self.order_moves(&mut moves, ply);
+        let mut best_score = f32::NEG_INFINITY;
+        let mut child_pv = vec![];
+        for mov in moves {
+            let undo = chessboard.make_move(mov);
+            self.position_keys.push(chessboard.zobrist_key);
+            let score = -self.negamax(chessboard, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            self.position_keys.pop();
+            chessboard.unmake_move(mov, undo);
+
+            if self.aborted {
+                return 0.0;
+            }
+            if score > best_score {
+                best_score = score;
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(mov);
+                    pv.extend_from_slice(&child_pv);
+                }
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        return best_score;
+    }
+}
+
+// Searches the current position of game, its earlier positions count for
+// repetitions. on_iteration is called after every completed depth. Returns the
+// last completed iteration, None when there are no legal moves.
+pub fn search(game: &Game, limits: SearchLimits, stop: &AtomicBool, mut on_iteration: impl FnMut(&SearchInfo)) -> Option<SearchInfo> {
+    let chessboard = game.board();
+    let legal_moves = chessboard.legal_moves(None);
+    if legal_moves.is_empty() {
+        return None;
+    }
+
+    let mut searcher = Searcher {
+        limits,
+        stop,
+        start: Instant::now(),
+        nodes: 0,
+        until_poll: POLL_INTERVAL,
+        aborted: false,
+        previous_pv: vec![],
+        position_keys: game.position_keys().to_vec(),
+    };
+    let mut chessboard = *chessboard;
+    let mut best: Option<SearchInfo> = None;
+
+    for depth in 1..=limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH) {
+        let mut pv = vec![];
+        let score = searcher.negamax(&mut chessboard, depth, 0, f32::NEG_INFINITY, f32::INFINITY, &mut pv);
+
+        if searcher.aborted {
+            // the root moves searched so far are still better than a blind guess
+            if best.is_none() && !pv.is_empty() {
+                best = Some(SearchInfo { depth: 0, score: 0.0, nodes: searcher.nodes, time: searcher.start.elapsed(), pv: pv[..1].to_vec() });
+            }
+            break;
+        }
+        let info = SearchInfo { depth, score, nodes: searcher.nodes, time: searcher.start.elapsed(), pv: pv.clone() };
+        on_iteration(&info);
+        searcher.previous_pv = pv;
+        best = Some(info);
+
+        // no point looking deeper once a forced mate is found
+        if score.abs() >= MATE_THRESHOLD {
+            break;
+        }
+    }
+
+    // stopped before a single root move was searched, play any legal move
+    return best.or_else(|| {
+        Some(SearchInfo { depth: 0, score: 0.0, nodes: searcher.nodes, time: searcher.start.elapsed(), pv: vec![legal_moves[0]] })
+    });
+}
@@ -0,0 +1,315 @@
+// Universal Chess Interface
+//
+// Moves travel between a GUI and the engine in long algebraic notation:
+// e2e4, e7e8q, e1g1 (castling is the king move). Formatting lives on Move
+// (see chess_move.rs), parsing needs the position to recover the move flags.
+//
+// run_uci() speaks the protocol over any input and output, uci_loop() over
+// stdin and stdout. The GUI sets up a position with `position`, starts
+// thinking with `go` and interrupts it with `stop`. The search runs on its own
+// thread so the loop keeps reading commands, it reports every finished depth
+// as an `info` line and ends with `bestmove`.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::chess_move::Move;
+use crate::chessboard::chessboard::{ChessBoard, EnumSquare};
+use crate::fen::piece_from_char;
+use crate::game::Game;
+use crate::search::{search, SearchInfo, SearchLimits};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UciMoveError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UciMoveError::InvalidSyntax(text) => write!(f, "'{}' is not a move in long algebraic notation", text),
+            UciMoveError::IllegalMove(text) => write!(f, "'{}' is not a legal move in this position", text),
+        }
+    }
+}
+
+impl std::error::Error for UciMoveError {}
+
+impl ChessBoard {
+    pub fn parse_uci_move(&self, text: &str) -> Result<Move, UciMoveError> {
+        let invalid = || UciMoveError::InvalidSyntax(text.to_string());
+
+        let text = text.trim();
+        if !text.is_ascii() || (text.len() != 4 && text.len() != 5) {
+            return Err(invalid());
+        }
+        let from = EnumSquare::from_name(&text[0..2]).ok_or_else(invalid)?.index();
+        let to = EnumSquare::from_name(&text[2..4]).ok_or_else(invalid)?.index();
+        let promotion = match text[4..].chars().next() {
+            None => None,
+            Some(character @ ('n' | 'b' | 'r' | 'q')) => piece_from_char(character),
+            Some(_) => return Err(invalid()),
+        };
+
+        return self
+            .legal_moves(None)
+            .into_iter()
+            .find(|mov| mov.from == from && mov.to == to && mov.promotion == promotion)
+            .ok_or_else(|| UciMoveError::IllegalMove(text.to_string()));
+    }
+}
+
+// moves left in the game when the GUI doesn't say (movestogo)
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+const DEFAULT_MOVE_OVERHEAD: u64 = 10;
+
+// Time for one move with time milliseconds left on the clock: an even share of
+// the moves to go plus half the increment, never more than the clock minus the
+// overhead, and the overhead taken off again for the delay of this move
+fn move_time(time: u64, increment: u64, moves_to_go: u64, move_overhead: u64) -> Duration {
+    let budget = (time / moves_to_go.max(1) + increment / 2).min(time.saturating_sub(move_overhead));
+    return Duration::from_millis(budget.saturating_sub(move_overhead).max(1));
+}
+
+// one line to the GUI, shared by the command loop and the search thread
+fn send<W: Write>(output: &Mutex<W>, line: &str) {
+    let mut output = output.lock().expect("output lock poisoned");
+    // nothing to do when the GUI is gone
+    let _ = writeln!(output, "{}", line).and_then(|_| output.flush());
+}
+
+struct UciEngine<W> {
+    game: Game,
+    stop: Arc<AtomicBool>,
+    search_thread: Option<JoinHandle<()>>,
+    // an infinite search only ends with stop
+    infinite: bool,
+    // milliseconds kept back from every move for communication delays
+    move_overhead: u64,
+    output: Arc<Mutex<W>>,
+}
+
+impl<W: Write + Send + 'static> UciEngine<W> {
+    fn new(output: W) -> Self {
+        Self {
+            game: Game::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+            search_thread: None,
+            infinite: false,
+            move_overhead: DEFAULT_MOVE_OVERHEAD,
+            output: Arc::new(Mutex::new(output)),
+        }
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.output, line);
+    }
+
+    fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait_for_search();
+    }
+
+    fn wait_for_search(&mut self) {
+        if let Some(search_thread) = self.search_thread.take() {
+            search_thread.join().expect("search thread panicked");
+        }
+    }
+
+    // setoption name <name> [value <value>], the name may contain spaces
+    fn set_option(&mut self, arguments: &[&str]) {
+        let value_index = arguments.iter().position(|argument| *argument == "value").unwrap_or(arguments.len());
+        let name = arguments.get(1..value_index).unwrap_or_default().join(" ");
+        let value = arguments.get(value_index + 1..).unwrap_or_default().join(" ");
+
+        match name.to_lowercase().as_str() {
+            "move overhead" => match value.parse() {
+                Ok(move_overhead) => self.move_overhead = move_overhead,
+                Err(_) => self.send(&format!("info string invalid value '{}' for option {}", value, name)),
+            },
+            _ => self.send(&format!("info string unknown option '{}'", name)),
+        }
+    }
+
+    // position [startpos | fen <fen>] [moves <move> ...]
+    fn set_position(&mut self, arguments: &[&str]) {
+        let moves_index = arguments.iter().position(|argument| *argument == "moves").unwrap_or(arguments.len());
+        let game = match arguments.first() {
+            Some(&"startpos") => Ok(Game::new()),
+            Some(&"fen") => Game::from_fen(&arguments[1..moves_index].join(" ")).map_err(|error| error.to_string()),
+            _ => Err("expected startpos or fen".to_string()),
+        };
+        let mut game = match game {
+            Ok(game) => game,
+            Err(error) => {
+                self.send(&format!("info string invalid position: {}", error));
+                return;
+            }
+        };
+
+        for text in arguments.iter().skip(moves_index + 1) {
+            match game.board().parse_uci_move(text) {
+                Ok(mov) => game.play(mov).expect("parse_uci_move only returns legal moves"),
+                Err(error) => {
+                    // keep the position up to the bad move, it's the best guess there is
+                    self.send(&format!("info string {}", error));
+                    break;
+                }
+            }
+        }
+        self.game = game;
+    }
+
+    // go [depth <d>] [nodes <n>] [movetime <ms>] [wtime <ms>] [btime <ms>]
+    //    [winc <ms>] [binc <ms>] [movestogo <n>] [infinite]
+    fn go(&mut self, arguments: &[&str]) {
+        self.stop_search();
+
+        let mut limits = SearchLimits::default();
+        let (mut time, mut increment, mut moves_to_go) = (None, 0, DEFAULT_MOVES_TO_GO);
+        let mut infinite = false;
+        let white_to_move = self.game.board().white_to_move;
+
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            let mut value = || arguments.next().and_then(|value| value.parse::<u64>().ok());
+            match *argument {
+                "depth" => limits.depth = value().map(|depth| depth as u32),
+                "nodes" => limits.nodes = value(),
+                "movetime" => limits.movetime = value().map(Duration::from_millis),
+                "wtime" if white_to_move => time = value(),
+                "btime" if !white_to_move => time = value(),
+                "winc" if white_to_move => increment = value().unwrap_or(0),
+                "binc" if !white_to_move => increment = value().unwrap_or(0),
+                "movestogo" => moves_to_go = value().unwrap_or(DEFAULT_MOVES_TO_GO).max(1),
+                "infinite" => infinite = true,
+                // the other side's clock
+                "wtime" | "btime" | "winc" | "binc" => {
+                    value();
+                }
+                _ => {}
+            }
+        }
+
+        // CLOCK
+        if let (Some(time), None) = (time, limits.movetime) {
+            limits.movetime = Some(move_time(time, increment, moves_to_go, self.move_overhead));
+        }
+
+        self.stop.store(false, Ordering::Relaxed);
+        self.infinite = infinite;
+        let stop = Arc::clone(&self.stop);
+        let output = Arc::clone(&self.output);
+        let game = self.game.clone();
+        self.search_thread = Some(thread::spawn(move || {
+            let result = search(&game, limits, &stop, |info| send(&output, &format_info(info)));
+
+            // in infinite mode bestmove may only be sent after stop
+            while infinite && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            match result.and_then(|result| result.pv.first().copied()) {
+                Some(mov) => send(&output, &format!("bestmove {}", mov)),
+                None => send(&output, "bestmove 0000"),
+            }
+        }));
+    }
+}
+
+fn format_info(info: &SearchInfo) -> String {
+    let score = match info.mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", (info.score * 100.0).round() as i32),
+    };
+    let milliseconds = info.time.as_millis() as u64;
+    let nps = info.nodes * 1000 / milliseconds.max(1);
+    let pv: Vec<String> = info.pv.iter().map(Move::to_uci).collect();
+    return format!("info depth {} score {} nodes {} nps {} time {} pv {}", info.depth, score, info.nodes, nps, milliseconds, pv.join(" "));
+}
+
+// Answers the commands read from input on output until quit or the end of
+// input, and hands output back. At the end of input a running search is
+// finished first (unless it is infinite), so piped commands get their bestmove.
+pub fn run_uci<R: BufRead, W: Write + Send + 'static>(input: R, output: W) -> W {
+    let mut engine = UciEngine::new(output);
+
+    for line in input.lines() {
+        let Ok(line) = line else { break };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((command, arguments)) = words.split_first() else { continue };
+
+        match *command {
+            "uci" => {
+                engine.send(&format!("id name {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")));
+                engine.send(&format!("id author the {} developers", env!("CARGO_PKG_NAME")));
+                engine.send(&format!("option name Move Overhead type spin default {} min 0 max 5000", DEFAULT_MOVE_OVERHEAD));
+                engine.send("uciok");
+            }
+            "isready" => engine.send("readyok"),
+            "setoption" => engine.set_option(arguments),
+            "ucinewgame" => {
+                engine.stop_search();
+                engine.game = Game::new();
+            }
+            "position" => {
+                engine.stop_search();
+                engine.set_position(arguments);
+            }
+            "go" => engine.go(arguments),
+            "stop" => engine.stop_search(),
+            "quit" => {
+                engine.stop_search();
+                break;
+            }
+            _ => engine.send(&format!("info string unknown command '{}'", line.trim())),
+        }
+    }
+
+    if engine.infinite {
+        engine.stop_search();
+    }
+    engine.wait_for_search();
+    let output = Arc::try_unwrap(engine.output).ok().expect("the search thread has ended");
+    return output.into_inner().expect("output lock poisoned");
+}
+
+// Speaks the protocol over stdin and stdout
+pub fn uci_loop() {
+    run_uci(io::stdin().lock(), io::stdout());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milliseconds(time: u64, increment: u64, moves_to_go: u64, move_overhead: u64) -> u128 {
+        move_time(time, increment, moves_to_go, move_overhead).as_millis()
+    }
+
+    #[test]
+    fn share_of_the_clock_plus_half_the_increment() {
+        assert_eq!(milliseconds(60_000, 0, 30, 10), 1990);
+        assert_eq!(milliseconds(60_000, 2000, 30, 10), 2990);
+        assert_eq!(milliseconds(60_000, 0, 1, 0), 60_000);
+        assert_eq!(milliseconds(60_000, 0, 10, 0), 6000);
+    }
+
+    #[test]
+    fn never_more_than_the_clock() {
+        // the increment alone would be more than what is left
+        assert_eq!(milliseconds(1000, 10_000, 30, 10), 980);
+        assert_eq!(milliseconds(1000, 0, 1, 100), 800);
+    }
+
+    #[test]
+    fn at_least_one_millisecond() {
+        assert_eq!(milliseconds(5, 0, 30, 10), 1);
+        assert_eq!(milliseconds(0, 0, 30, 0), 1);
+        assert_eq!(milliseconds(100, 0, 0, 0), 100);
+    }
+}
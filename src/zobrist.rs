@@ -0,0 +1,91 @@
+// Zobrist hashing
+//
+// Every (color, piece, square) combination, the side to move, each set of
+// castling rights and each en passant file gets a random 64 bit key. The key
+// of a position is the XOR of the keys of everything in it, so a move only has
+// to XOR out what it removes and XOR in what it adds.
+//
+// The en passant file is only part of the key while a pawn of the side to move
+// attacks the en passant square (see ChessBoard::compute_zobrist_key), like in
+// the repetition rules a capture that can't be made doesn't make a different
+// position.
+
+use crate::chess_move::Piece;
+
+pub(crate) struct ZobristKeys {
+    pub pieces: [[[u64; 64]; 6]; 2],
+    pub black_to_move: u64,
+    pub castling_rights: [u64; 16],
+    pub en_passant_file: [u64; 8],
+}
+
+// splitmix64, so the keys are fixed at compile time and identical between runs
+const fn next_random(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    return (state, z ^ (z >> 31));
+}
+
+impl ZobristKeys {
+    const fn generate() -> Self {
+        let mut keys = ZobristKeys {
+            pieces: [[[0; 64]; 6]; 2],
+            black_to_move: 0,
+            castling_rights: [0; 16],
+            en_passant_file: [0; 8],
+        };
+        let mut state: u64 = 0x2545F4914F6CDD1D;
+        let mut random;
+
+        let mut color = 0;
+        while color < 2 {
+            let mut piece = 0;
+            while piece < 6 {
+                let mut square = 0;
+                while square < 64 {
+                    (state, random) = next_random(state);
+                    keys.pieces[color][piece][square] = random;
+                    square += 1;
+                }
+                piece += 1;
+            }
+            color += 1;
+        }
+
+        (state, random) = next_random(state);
+        keys.black_to_move = random;
+
+        // no rights hash to 0 so a board without castling needs no extra key
+        let mut rights = 1;
+        while rights < 16 {
+            (state, random) = next_random(state);
+            keys.castling_rights[rights] = random;
+            rights += 1;
+        }
+
+        let mut file = 0;
+        while file < 8 {
+            (state, random) = next_random(state);
+            keys.en_passant_file[file] = random;
+            file += 1;
+        }
+
+        return keys;
+    }
+
+    pub fn piece(&self, white: bool, piece: Piece, square: u64) -> u64 {
+        let color = if white { 0 } else { 1 };
+        return self.pieces[color][piece as usize][square.trailing_zeros() as usize];
+    }
+
+    pub fn en_passant(&self, en_passant_square: Option<u8>) -> u64 {
+        match en_passant_square {
+            Some(square) => self.en_passant_file[(square % 8) as usize],
+            None => 0,
+        }
+    }
+}
+
+pub(crate) static ZOBRIST_KEYS: ZobristKeys = ZobristKeys::generate();
@@ -0,0 +1,83 @@
+// Attacked squares and attackers
+
+use chess_engine::{ChessBoard, Color, EnumSquare};
+
+fn board(fen: &str) -> ChessBoard {
+    ChessBoard::from_fen(fen).unwrap()
+}
+
+fn squares(names: &[EnumSquare]) -> u64 {
+    names.iter().map(|square| square.bitboard()).sum()
+}
+
+#[test]
+fn pawns_attack_forward_diagonally() {
+    // white pawn on e4, black pawn on d5
+    let chessboard = board("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+
+    assert!(chessboard.is_square_attacked(EnumSquare::d5.bitboard(), Color::White));
+    assert!(chessboard.is_square_attacked(EnumSquare::f5.bitboard(), Color::White));
+    assert!(!chessboard.is_square_attacked(EnumSquare::e5.bitboard(), Color::White));
+    assert!(!chessboard.is_square_attacked(EnumSquare::d3.bitboard(), Color::White));
+
+    assert!(chessboard.is_square_attacked(EnumSquare::e4.bitboard(), Color::Black));
+    assert!(chessboard.is_square_attacked(EnumSquare::c4.bitboard(), Color::Black));
+    assert!(!chessboard.is_square_attacked(EnumSquare::d4.bitboard(), Color::Black));
+    assert!(!chessboard.is_square_attacked(EnumSquare::e6.bitboard(), Color::Black));
+}
+
+#[test]
+fn pawn_attacks_dont_wrap_around_the_board() {
+    let chessboard = board("4k3/7p/8/8/8/8/P7/4K3 w - - 0 1");
+    assert!(chessboard.is_square_attacked(EnumSquare::b3.bitboard(), Color::White));
+    assert!(!chessboard.is_square_attacked(EnumSquare::h4.bitboard(), Color::White));
+    assert!(chessboard.is_square_attacked(EnumSquare::g6.bitboard(), Color::Black));
+    assert!(!chessboard.is_square_attacked(EnumSquare::a5.bitboard(), Color::Black));
+}
+
+#[test]
+fn sliders_stop_at_the_first_piece() {
+    // rook on a1, knight on a4 in the way
+    let chessboard = board("4k3/8/8/8/N7/8/8/R3K3 w - - 0 1");
+    assert!(chessboard.is_square_attacked(EnumSquare::a3.bitboard(), Color::White));
+    // the blocking piece itself is attacked (defended), the squares behind it aren't
+    assert!(chessboard.is_square_attacked(EnumSquare::a4.bitboard(), Color::White));
+    assert!(!chessboard.is_square_attacked(EnumSquare::a6.bitboard(), Color::White));
+    // along the rank up to the own king
+    assert!(chessboard.is_square_attacked(EnumSquare::d1.bitboard(), Color::White));
+
+    // the same for a bishop, blocked by an enemy pawn
+    let chessboard = board("4k3/8/8/8/3p4/8/1B6/4K3 w - - 0 1");
+    assert!(chessboard.is_square_attacked(EnumSquare::c3.bitboard(), Color::White));
+    assert!(chessboard.is_square_attacked(EnumSquare::d4.bitboard(), Color::White));
+    assert!(!chessboard.is_square_attacked(EnumSquare::e5.bitboard(), Color::White));
+    let chessboard = board("4k3/8/8/8/8/8/1B6/4K3 w - - 0 1");
+    assert!(chessboard.is_square_attacked(EnumSquare::e5.bitboard(), Color::White));
+    assert!(chessboard.is_square_attacked(EnumSquare::h8.bitboard(), Color::White));
+}
+
+#[test]
+fn a_square_is_not_attacked_by_the_piece_on_it() {
+    let chessboard = board("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+    assert!(!chessboard.is_square_attacked(EnumSquare::a1.bitboard(), Color::White));
+    assert!(!chessboard.is_square_attacked(EnumSquare::a1.bitboard(), Color::Black));
+}
+
+#[test]
+fn attackers_of_both_colors() {
+    // e4 is attacked by the white knight on f2, the black pawn on d5 and the
+    // black bishop on h7, the queen on e1 and the rook on e8 are blocked by
+    // their own pawns on e2 and e6
+    let chessboard = board("4r1k1/7b/4p3/3p4/4P3/8/4PN2/4Q1K1 w - - 0 1");
+    let e4 = EnumSquare::e4.bitboard();
+    assert_eq!(chessboard.attackers_to(e4), squares(&[EnumSquare::f2, EnumSquare::d5, EnumSquare::h7]));
+
+    // with the pawn on e2 out of the way the queen joins in
+    let chessboard = board("4r1k1/7b/4p3/3p4/4P3/8/5N2/4Q1K1 w - - 0 1");
+    assert_eq!(chessboard.attackers_to(e4), squares(&[EnumSquare::e1, EnumSquare::f2, EnumSquare::d5, EnumSquare::h7]));
+
+    // kings count as attackers too
+    let chessboard = board("8/8/8/3k4/8/3K4/8/8 w - - 0 1");
+    assert_eq!(chessboard.attackers_to(EnumSquare::d4.bitboard()), squares(&[EnumSquare::d3, EnumSquare::d5]));
+    assert_eq!(chessboard.attackers_to(EnumSquare::a8.bitboard()), 0);
+}
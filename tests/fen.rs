@@ -0,0 +1,104 @@
+// FEN import and export
+
+use chess_engine::{CastlingRights, ChessBoard, EnumSquare, FenError, STARTING_FEN};
+
+fn error(fen: &str) -> FenError {
+    ChessBoard::from_fen(fen).unwrap_err()
+}
+
+#[test]
+fn round_trips() {
+    for fen in [
+        STARTING_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3",
+        "rnbqkbnr/pppp1ppp/8/3Pp3/8/8/PPP1PPPP/RNBQKBNR w Kq e6 0 3",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 37 112",
+    ] {
+        assert_eq!(ChessBoard::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn starting_position_matches_new() {
+    assert_eq!(ChessBoard::from_fen(STARTING_FEN).unwrap(), ChessBoard::new());
+    assert_eq!(ChessBoard::new().to_fen(), STARTING_FEN);
+}
+
+#[test]
+fn reads_every_field() {
+    let chessboard = ChessBoard::from_fen("r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 4 20").unwrap();
+    assert!(chessboard.white_to_move);
+    assert_eq!(chessboard.castling_rights, CastlingRights::WHITE_SHORT | CastlingRights::BLACK_LONG);
+    assert_eq!(chessboard.en_passant_square, Some(EnumSquare::d6.index()));
+    assert_eq!(chessboard.halfmove_clock, 4);
+    assert_eq!(chessboard.fullmove_number, 20);
+}
+
+#[test]
+fn counters_are_optional() {
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+    assert_eq!(chessboard.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn field_count_errors() {
+    assert_eq!(error(""), FenError::MissingField("piece placement"));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3"), FenError::MissingField("side to move"));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w"), FenError::MissingField("castling rights"));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w -"), FenError::MissingField("en passant square"));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra"), FenError::TooManyFields(7));
+}
+
+#[test]
+fn piece_placement_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/4K3 w - - 0 1"), FenError::WrongNumberOfRanks(7));
+    assert_eq!(error("4k3/8/8/8/8/8/8/8/4K3 w - - 0 1"), FenError::WrongNumberOfRanks(9));
+    assert_eq!(error("4k3/8/8/8/3X4/8/8/4K3 w - - 0 1"), FenError::InvalidPiece { rank: 4, character: 'X' });
+    assert_eq!(error("4k3/8/8/8/08/8/8/4K3 w - - 0 1"), FenError::InvalidPiece { rank: 4, character: '0' });
+    assert_eq!(error("4k3/8/8/8/7/8/8/4K3 w - - 0 1"), FenError::WrongRankLength { rank: 4, squares: 7 });
+    assert_eq!(error("4k3/8/8/8/8p/8/8/4K3 w - - 0 1"), FenError::WrongRankLength { rank: 4, squares: 9 });
+    assert_eq!(error("4k2p/8/8/8/8/8/8/4K3 w - - 0 1"), FenError::PawnOnBackRank { rank: 8 });
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K2P w - - 0 1"), FenError::PawnOnBackRank { rank: 1 });
+    assert_eq!(error("4k3/8/8/8/8/8/8/8 w - - 0 1"), FenError::WrongKingCount { white: true, count: 0 });
+    assert_eq!(error("4k2k/8/8/8/8/8/8/4K3 w - - 0 1"), FenError::WrongKingCount { white: false, count: 2 });
+}
+
+#[test]
+fn side_to_move_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 x - - 0 1"), FenError::InvalidSideToMove("x".to_string()));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 W - - 0 1"), FenError::InvalidSideToMove("W".to_string()));
+}
+
+#[test]
+fn castling_rights_errors() {
+    assert_eq!(error("r3k2r/8/8/8/8/8/8/R3K2R w KX - 0 1"), FenError::InvalidCastlingRights("KX".to_string()));
+    assert_eq!(error("r3k2r/8/8/8/8/8/8/R3K2R w KK - 0 1"), FenError::InvalidCastlingRights("KK".to_string()));
+    // no rook on h1
+    assert_eq!(error("r3k2r/8/8/8/8/8/8/R3K3 w K - 0 1"), FenError::InvalidCastlingRights("K".to_string()));
+    // king not on e8
+    assert_eq!(error("r2k3r/8/8/8/8/8/8/R3K2R w q - 0 1"), FenError::InvalidCastlingRights("q".to_string()));
+}
+
+#[test]
+fn en_passant_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - z9 0 1"), FenError::InvalidEnPassantSquare("z9".to_string()));
+    // no pawn that could just have double pushed
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - d6 0 1"), FenError::InvalidEnPassantSquare("d6".to_string()));
+    // wrong rank for the side to move
+    assert_eq!(error("4k3/8/8/3p4/8/8/8/4K3 b - d6 0 1"), FenError::InvalidEnPassantSquare("d6".to_string()));
+}
+
+#[test]
+fn counter_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - -1 1"), FenError::InvalidHalfmoveClock("-1".to_string()));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - x 1"), FenError::InvalidHalfmoveClock("x".to_string()));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 0"), FenError::InvalidFullmoveNumber("0".to_string()));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 one"), FenError::InvalidFullmoveNumber("one".to_string()));
+}
+
+#[test]
+fn errors_name_the_culprit() {
+    assert_eq!(error("4k3/8/8/8/3X4/8/8/4K3 w - - 0 1").to_string(), "invalid piece 'X' on rank 4");
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - d6 0 1").to_string(), "invalid en passant square 'd6'");
+}
@@ -0,0 +1,166 @@
+// Game history and the draw rules
+
+use chess_engine::{Color, Game, GameStatus, IllegalMove};
+
+fn play(game: &mut Game, moves: &str) {
+    for text in moves.split_whitespace() {
+        let mov = game.board().parse_uci_move(text).unwrap();
+        game.play(mov).unwrap();
+    }
+}
+
+// both knights out and back, the starting position occurs once more
+const KNIGHT_SHUFFLE: &str = "g1f3 g8f6 f3g1 f6g8";
+
+#[test]
+fn play_and_undo() {
+    let mut game = Game::new();
+    play(&mut game, "e2e4 e7e5");
+    assert_eq!(game.moves().len(), 2);
+
+    let e5 = game.moves()[1];
+    assert_eq!(game.undo(), Some(e5));
+    assert_eq!(game.board().to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    assert_eq!(game.undo().map(|mov| mov.to_string()), Some("e2e4".to_string()));
+    assert_eq!(game.undo(), None);
+    assert_eq!(game.board(), game.starting_position());
+}
+
+#[test]
+fn illegal_moves_are_refused() {
+    let mut game = Game::new();
+    play(&mut game, "e2e4");
+    // legal for white a move ago
+    let d4 = Game::new().board().parse_uci_move("d2d4").unwrap();
+    assert_eq!(game.play(d4), Err(IllegalMove(d4)));
+    assert_eq!(game.moves().len(), 1);
+}
+
+#[test]
+fn repetition_count_follows_undo() {
+    let mut game = Game::new();
+    assert_eq!(game.repetition_count(), 1);
+
+    play(&mut game, KNIGHT_SHUFFLE);
+    assert_eq!(game.repetition_count(), 2);
+    play(&mut game, KNIGHT_SHUFFLE);
+    assert_eq!(game.repetition_count(), 3);
+    assert!(game.is_threefold_repetition());
+
+    // the position after Nf3 Nf6 has occurred twice
+    game.undo();
+    game.undo();
+    assert_eq!(game.repetition_count(), 2);
+    assert!(!game.is_threefold_repetition());
+
+    play(&mut game, "f3g1 f6g8");
+    assert_eq!(game.repetition_count(), 3);
+
+    while game.undo().is_some() {}
+    assert_eq!(game.repetition_count(), 1);
+}
+
+#[test]
+fn irreversible_moves_end_the_repetitions() {
+    let mut game = Game::new();
+    play(&mut game, KNIGHT_SHUFFLE);
+    play(&mut game, KNIGHT_SHUFFLE);
+    play(&mut game, "e2e3 e7e6");
+    play(&mut game, KNIGHT_SHUFFLE);
+    assert_eq!(game.repetition_count(), 2);
+}
+
+#[test]
+fn en_passant_square_without_a_capture_is_the_same_position() {
+    let mut game = Game::new();
+    // no black pawn can take on e3, so the position after 1. e4 occurs three times
+    play(&mut game, "e2e4 g8f6 g1f3 f6g8 f3g1 g8f6 g1f3 f6g8 f3g1");
+    assert_eq!(game.repetition_count(), 3);
+    assert_eq!(game.status(), GameStatus::ThreefoldRepetition);
+
+    let with_square = Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+    let without_square = Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(with_square.board().zobrist_key, without_square.board().zobrist_key);
+    // the square itself is kept for the FEN
+    assert_eq!(with_square.board().to_fen(), "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1");
+}
+
+#[test]
+fn en_passant_square_with_a_capture_is_another_position() {
+    let with_square = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    let without_square = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(with_square.board().zobrist_key, without_square.board().zobrist_key);
+
+    // after d5 the white pawn could take en passant, once the chance is gone
+    // the same placement is a new position
+    let mut game = Game::from_fen("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1").unwrap();
+    play(&mut game, "d7d5 e1e2 e8d8 e2e1 d8e8");
+    assert_eq!(game.repetition_count(), 1);
+}
+
+#[test]
+fn threefold_repetition_can_be_claimed() {
+    let mut game = Game::new();
+    play(&mut game, KNIGHT_SHUFFLE);
+    play(&mut game, KNIGHT_SHUFFLE);
+    assert_eq!(game.status(), GameStatus::ThreefoldRepetition);
+    assert_eq!(game.status().result(), "1/2-1/2");
+
+    // and may be ignored
+    play(&mut game, "e2e4");
+    assert_eq!(game.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn fivefold_repetition_ends_the_game() {
+    let mut game = Game::new();
+    for _ in 0..3 {
+        play(&mut game, KNIGHT_SHUFFLE);
+    }
+    assert_eq!(game.repetition_count(), 4);
+    assert!(!game.is_fivefold_repetition());
+    assert_eq!(game.status(), GameStatus::ThreefoldRepetition);
+
+    play(&mut game, KNIGHT_SHUFFLE);
+    assert_eq!(game.repetition_count(), 5);
+    assert!(game.is_fivefold_repetition());
+    assert_eq!(game.status(), GameStatus::FivefoldRepetition);
+}
+
+#[test]
+fn fifty_move_rule_can_be_claimed() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+    assert!(!game.is_fifty_move_rule());
+    assert_eq!(game.status(), GameStatus::Ongoing);
+
+    play(&mut game, "a1a2");
+    assert!(game.is_fifty_move_rule());
+    assert_eq!(game.status(), GameStatus::FiftyMoveRule);
+
+    game.undo();
+    assert_eq!(game.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn seventy_five_move_rule_ends_the_game() {
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 149 105").unwrap();
+    assert!(!game.is_seventy_five_move_rule());
+    assert_eq!(game.status(), GameStatus::FiftyMoveRule);
+
+    play(&mut game, "a1a2");
+    assert!(game.is_seventy_five_move_rule());
+    assert_eq!(game.status(), GameStatus::SeventyFiveMoveRule);
+
+    // a pawn move or capture resets the count
+    let mut game = Game::from_fen("4k3/8/8/8/8/8/P7/R3K3 w - - 149 105").unwrap();
+    play(&mut game, "a2a3");
+    assert_eq!(game.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn mate_on_the_last_move_still_counts() {
+    let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 105").unwrap();
+    play(&mut game, "a1a8");
+    assert!(game.is_seventy_five_move_rule());
+    assert_eq!(game.status(), GameStatus::Checkmate { winner: Color::White });
+}
@@ -0,0 +1,288 @@
+// Perft regression suite
+//
+// Node counts from the start position and the well known test positions
+// (https://www.chessprogramming.org/Perft_Results and the perftsuite posted on
+// talkchess). Every test checks all depths up to its deepest count, the
+// deeper ones take a while and are ignored by default:
+//
+//     cargo test --release -- --ignored
+
+use chess_engine::{ChessBoard, PerftStats, STARTING_FEN};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+const PROMOTIONS: &str = "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1";
+
+// expected[i] is the count at depth i + 1
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let chessboard = ChessBoard::from_fen(fen).unwrap();
+    for (i, nodes) in expected.iter().enumerate() {
+        let depth = i as u64 + 1;
+        assert_eq!(chessboard.perft(depth).1, *nodes, "perft({}) of {}", depth, fen);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn stats(
+    nodes: u64,
+    captures: u64,
+    en_passant: u64,
+    castles: u64,
+    promotions: u64,
+    checks: u64,
+    discovered_checks: u64,
+    double_checks: u64,
+    checkmates: u64,
+) -> PerftStats {
+    PerftStats { nodes, captures, en_passant, castles, promotions, checks, discovered_checks, double_checks, checkmates }
+}
+
+// STANDARD POSITIONS
+
+#[test]
+fn starting_position() {
+    assert_perft(STARTING_FEN, &[20, 400, 8902, 197281]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn starting_position_deep() {
+    assert_perft(STARTING_FEN, &[20, 400, 8902, 197281, 4865609, 119060324]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn kiwipete_deep() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862, 4085603, 193690690]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238, 674624]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn position_3_deep() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238, 674624, 11030083]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9467, 422333]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467, 422333]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn position_4_deep() {
+    assert_perft(POSITION_4, &[6, 264, 9467, 422333, 15833292]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467, 422333, 15833292]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn position_5_deep() {
+    assert_perft(POSITION_5, &[44, 1486, 62379, 2103487, 89941194]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft(POSITION_6, &[46, 2079, 89890]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn position_6_deep() {
+    assert_perft(POSITION_6, &[46, 2079, 89890, 3894594]);
+}
+
+#[test]
+fn promotions() {
+    assert_perft(PROMOTIONS, &[24, 496, 9483, 182838]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn promotions_deep() {
+    assert_perft(PROMOTIONS, &[24, 496, 9483, 182838, 3605103]);
+}
+
+// EN PASSANT
+
+#[test]
+fn illegal_en_passant_exposes_king() {
+    assert_perft("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", &[18, 92, 1670, 10138, 185429]);
+}
+
+#[test]
+fn en_passant_capture_checks_opponent() {
+    assert_perft("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", &[15, 126, 1928, 13931, 206379]);
+}
+
+#[test]
+fn avoid_illegal_en_passant_capture() {
+    assert_perft("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", &[13, 102, 1266, 10276, 135655]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn en_passant_deep() {
+    assert_perft("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", &[18, 92, 1670, 10138, 185429, 1134888]);
+    assert_perft("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", &[15, 126, 1928, 13931, 206379, 1440467]);
+    assert_perft("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", &[13, 102, 1266, 10276, 135655, 1015133]);
+}
+
+// CASTLING
+
+#[test]
+fn short_castling_gives_check() {
+    assert_perft("5k2/8/8/8/8/8/8/4K2R w K - 0 1", &[15, 66, 1198, 6399, 120330]);
+}
+
+#[test]
+fn long_castling_gives_check() {
+    assert_perft("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", &[16, 71, 1286, 7418, 141077]);
+}
+
+#[test]
+fn castling_rights_lost_by_captures() {
+    assert_perft("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", &[26, 1141, 27826]);
+}
+
+#[test]
+fn castling_prevented_by_attacks() {
+    assert_perft("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", &[44, 1494, 50509]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn castling_deep() {
+    assert_perft("5k2/8/8/8/8/8/8/4K2R w K - 0 1", &[15, 66, 1198, 6399, 120330, 661072]);
+    assert_perft("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", &[16, 71, 1286, 7418, 141077, 803711]);
+    assert_perft("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", &[26, 1141, 27826, 1274206]);
+    assert_perft("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", &[44, 1494, 50509, 1720476]);
+}
+
+// PROMOTIONS, CHECKS AND MATES
+
+#[test]
+fn promote_out_of_check() {
+    assert_perft("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", &[11, 133, 1442, 19174, 266199]);
+}
+
+#[test]
+fn promote_to_give_check() {
+    assert_perft("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", &[9, 40, 472, 2661, 38983]);
+}
+
+#[test]
+fn underpromote_to_check() {
+    assert_perft("8/P1k5/K7/8/8/8/8/8 w - - 0 1", &[6, 27, 273, 1329, 18135]);
+}
+
+#[test]
+fn discovered_check() {
+    assert_perft("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", &[29, 165, 5160, 31961]);
+}
+
+#[test]
+fn self_stalemate() {
+    assert_perft("K1k5/8/P7/8/8/8/8/8 w - - 0 1", &[2, 6, 13, 63, 382, 2217]);
+}
+
+#[test]
+fn stalemate_and_checkmate() {
+    assert_perft("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", &[10, 25, 268, 926, 10857, 43261]);
+    assert_perft("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", &[37, 183, 6559, 23527]);
+}
+
+#[test]
+#[ignore = "slow"]
+fn promotions_checks_and_mates_deep() {
+    assert_perft("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", &[11, 133, 1442, 19174, 266199, 3821001]);
+    assert_perft("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", &[9, 40, 472, 2661, 38983, 217342]);
+    assert_perft("8/P1k5/K7/8/8/8/8/8 w - - 0 1", &[6, 27, 273, 1329, 18135, 92683]);
+    assert_perft("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", &[29, 165, 5160, 31961, 1004658]);
+    assert_perft("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", &[10, 25, 268, 926, 10857, 43261, 567584]);
+}
+
+// DETAILED STATISTICS (the rows of the published tables)
+
+#[test]
+fn starting_position_stats() {
+    let chessboard = ChessBoard::from_fen(STARTING_FEN).unwrap();
+    assert_eq!(chessboard.perft_stats(3), stats(8902, 34, 0, 0, 0, 12, 0, 0, 0));
+    assert_eq!(chessboard.perft_stats(4), stats(197281, 1576, 0, 0, 0, 469, 0, 0, 8));
+}
+
+#[test]
+fn kiwipete_stats() {
+    let chessboard = ChessBoard::from_fen(KIWIPETE).unwrap();
+    assert_eq!(chessboard.perft_stats(1), stats(48, 8, 0, 2, 0, 0, 0, 0, 0));
+    assert_eq!(chessboard.perft_stats(2), stats(2039, 351, 1, 91, 0, 3, 0, 0, 0));
+    assert_eq!(chessboard.perft_stats(3), stats(97862, 17102, 45, 3162, 0, 993, 0, 0, 1));
+}
+
+#[test]
+fn position_3_stats() {
+    let chessboard = ChessBoard::from_fen(POSITION_3).unwrap();
+    assert_eq!(chessboard.perft_stats(3), stats(2812, 209, 2, 0, 0, 267, 3, 0, 0));
+    assert_eq!(chessboard.perft_stats(4), stats(43238, 3348, 123, 0, 0, 1680, 106, 0, 17));
+}
+
+#[test]
+#[ignore = "slow"]
+fn stats_deep() {
+    let chessboard = ChessBoard::from_fen(STARTING_FEN).unwrap();
+    assert_eq!(chessboard.perft_stats(5), stats(4865609, 82719, 258, 0, 0, 27351, 6, 0, 347));
+    let chessboard = ChessBoard::from_fen(KIWIPETE).unwrap();
+    assert_eq!(chessboard.perft_stats(4), stats(4085603, 757163, 1929, 128013, 15172, 25523, 42, 6, 43));
+    let chessboard = ChessBoard::from_fen(POSITION_3).unwrap();
+    assert_eq!(chessboard.perft_stats(5), stats(674624, 52051, 1165, 0, 0, 52950, 1292, 3, 0));
+}
+
+// PARALLEL PERFT
+
+#[test]
+fn parallel_perft_matches_sequential() {
+    let chessboard = ChessBoard::from_fen(KIWIPETE).unwrap();
+    for threads in [1, 3, 64] {
+        assert_eq!(chessboard.parallel_perft(3, threads), 97862);
+        assert_eq!(chessboard.parallel_divide(3, threads), chessboard.divide(3));
+        assert_eq!(chessboard.parallel_perft_stats(3, threads), chessboard.perft_stats(3));
+    }
+    assert_eq!(chessboard.parallel_perft(0, 4), 1);
+    assert_eq!(chessboard.parallel_perft_stats(1, 4), stats(48, 8, 0, 2, 0, 0, 0, 0, 0));
+}
+
+#[test]
+fn parallel_perft_without_legal_moves() {
+    // black is mated
+    let chessboard = ChessBoard::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    assert_eq!(chessboard.parallel_perft(2, 4), 0);
+    assert!(chessboard.parallel_divide(2, 4).is_empty());
+}
+
+#[test]
+#[ignore = "slow"]
+fn parallel_perft_deep() {
+    let chessboard = ChessBoard::from_fen(STARTING_FEN).unwrap();
+    assert_eq!(chessboard.parallel_perft(6, 8), 119060324);
+    let chessboard = ChessBoard::from_fen(KIWIPETE).unwrap();
+    assert_eq!(chessboard.parallel_perft(5, 8), 193690690);
+}
@@ -0,0 +1,250 @@
+// Portable Game Notation
+
+use chess_engine::{parse_pgn, write_pgn, ChessBoard, FenError, Move, PgnError, SanError};
+
+// moves of the main line in coordinate notation
+fn main_line(pgn: &str) -> Vec<String> {
+    let games = parse_pgn(pgn).unwrap();
+    assert_eq!(games.len(), 1);
+    games[0].game.moves().iter().map(|mov| mov.to_string()).collect()
+}
+
+// READING
+
+#[test]
+fn tags_and_result() {
+    let games = parse_pgn(
+        r#"[Event "Casual \"blitz\" game"]
+[Site "C:\\games"]
+[White "Morphy"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 1-0
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(games.len(), 1);
+    let game = &games[0];
+    assert_eq!(game.tag("Event"), Some("Casual \"blitz\" game"));
+    assert_eq!(game.tag("Site"), Some("C:\\games"));
+    assert_eq!(game.tag("White"), Some("Morphy"));
+    assert_eq!(game.tag("Black"), None);
+    assert_eq!(game.result, "1-0");
+    assert_eq!(game.game.moves().len(), 3);
+    assert_eq!(*game.game.starting_position(), ChessBoard::new());
+}
+
+#[test]
+fn comments_and_annotations_are_skipped() {
+    let pgn = "1. e4 {best by test} e5 $1 2. Nf3!? ; rest of the line\n Nc6?? 3. Bb5 $14 {} a6 *";
+    assert_eq!(main_line(pgn), ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6"]);
+}
+
+#[test]
+fn move_numbers_may_be_glued_to_moves() {
+    assert_eq!(main_line("1.e4 e5 2.Nf3 2...Nc6 *"), ["e2e4", "e7e5", "g1f3", "b8c6"]);
+}
+
+#[test]
+fn nested_variations_stay_out_of_the_main_line() {
+    let pgn = "1. e4 e5 (1... c5 2. Nf3 (2. c3 d5 1-0) d6) (1... e6) 2. Nf3 (2. f4 exf4 {gambit}) Nc6 *";
+    assert_eq!(main_line(pgn), ["e2e4", "e7e5", "g1f3", "b8c6"]);
+}
+
+#[test]
+fn percent_lines_are_escaped() {
+    let pgn = "% exported by some tool 1. d4\n1. e4 e5\n%2. Nf3\n2. Bc4 *";
+    assert_eq!(main_line(pgn), ["e2e4", "e7e5", "f1c4"]);
+}
+
+#[test]
+fn multiple_games() {
+    let pgn = r#"[Event "First"]
+
+1. e4 e5 1/2-1/2
+
+[Event "Second"]
+
+1. d4 0-1
+
+[Event "Third"]
+
+1. c4
+
+[Event "Fourth"]
+"#;
+    let games = parse_pgn(pgn).unwrap();
+    let summary: Vec<(Option<&str>, usize, &str)> =
+        games.iter().map(|game| (game.tag("Event"), game.game.moves().len(), game.result.as_str())).collect();
+    assert_eq!(summary, [(Some("First"), 2, "1/2-1/2"), (Some("Second"), 1, "0-1"), (Some("Third"), 1, "*"), (Some("Fourth"), 0, "*")]);
+}
+
+#[test]
+fn starts_from_the_fen_tag() {
+    let pgn = r#"[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"]
+
+1... Kd7 2. e4 *"#;
+    let games = parse_pgn(pgn).unwrap();
+    assert_eq!(games[0].game.starting_position().to_fen(), "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1");
+    assert_eq!(games[0].game.board().to_fen(), "8/3k4/8/8/4P3/8/8/4K3 b - e3 0 2");
+}
+
+#[test]
+fn illegal_move_reports_ply_and_text() {
+    let error = parse_pgn("1. e4 e5 2. Nf3 Nc6 3. Bb5 Nf3 *").unwrap_err();
+    assert_eq!(error, PgnError::IllegalMove { ply: 6, text: "Nf3".to_string(), reason: SanError::IllegalMove("Nf3".to_string()) });
+    assert_eq!(error.to_string(), "ply 6: 'Nf3' is not a legal move in this position");
+
+    // a variation replaces the move before it, the main line goes on after it
+    let error = parse_pgn("1. e4 (1. d4 d5) e5 2. Ke3 *").unwrap_err();
+    assert!(matches!(error, PgnError::IllegalMove { ply: 3, ref text, .. } if text == "Ke3"));
+
+    let error = parse_pgn("1. e4 e5 2. Nz3 *").unwrap_err();
+    assert_eq!(error, PgnError::IllegalMove { ply: 3, text: "Nz3".to_string(), reason: SanError::InvalidSyntax("Nz3".to_string()) });
+}
+
+#[test]
+fn illegal_moves_in_variations_are_reported() {
+    let error = parse_pgn("1. e4 e5 (1... c5 2. Qxf7) 2. Nf3 *").unwrap_err();
+    assert_eq!(error, PgnError::IllegalMove { ply: 3, text: "Qxf7".to_string(), reason: SanError::IllegalMove("Qxf7".to_string()) });
+
+    // nested, the inner variation starts before 2. Nf3 of the outer one
+    let error = parse_pgn("1. e4 e5 (1... c5 2. Nf3 (2. c3 Nf6 3. e5 Nd5 4. Nxd5) d6) 2. Nf3 *").unwrap_err();
+    assert!(matches!(error, PgnError::IllegalMove { ply: 7, ref text, .. } if text == "Nxd5"));
+
+    // a second variation starts from the same position as the first
+    let error = parse_pgn("1. e4 e5 (1... c5) (1... Nf3) 2. Nf3 *").unwrap_err();
+    assert!(matches!(error, PgnError::IllegalMove { ply: 2, ref text, .. } if text == "Nf3"));
+
+    // legal variations don't change the main line
+    assert_eq!(main_line("1. e4 e5 (1... c5 2. Nf3 (2. c3 Nf6) d6) (1... e6 2. d4) 2. Nf3 *"), ["e2e4", "e7e5", "g1f3"]);
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(parse_pgn("[Event Casual]\n1. e4 *").unwrap_err(), PgnError::InvalidTag("[Event Casual]".to_string()));
+    assert_eq!(parse_pgn("[\"Casual\"]\n1. e4 *").unwrap_err(), PgnError::InvalidTag("[\"Casual\"]".to_string()));
+    assert_eq!(parse_pgn("1. e4 {never closed e5 *").unwrap_err(), PgnError::UnterminatedComment);
+    assert_eq!(parse_pgn("1. e4 (1. d4 e5 *").unwrap_err(), PgnError::UnbalancedVariation);
+    assert_eq!(parse_pgn("1. e4 1. d4) e5 *").unwrap_err(), PgnError::UnbalancedVariation);
+    assert_eq!(
+        parse_pgn("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*").unwrap_err(),
+        PgnError::InvalidFen(FenError::WrongKingCount { white: true, count: 0 })
+    );
+}
+
+// WRITING
+
+fn tags(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+// plays the moves in SAN from the position
+fn san_moves(chessboard: &ChessBoard, moves: &str) -> Vec<Move> {
+    let mut chessboard = *chessboard;
+    let mut result = vec![];
+    for san in moves.split_whitespace() {
+        let mov = chessboard.parse_san(san).unwrap();
+        chessboard.make_move(mov);
+        result.push(mov);
+    }
+    result
+}
+
+#[test]
+fn writes_the_seven_tag_roster_first() {
+    let chessboard = ChessBoard::new();
+    let moves = san_moves(&chessboard, "e4 e5 Nf3");
+    let pgn = write_pgn(&chessboard, &moves, &tags(&[("Annotator", "me"), ("White", "A \"quoted\" name"), ("Result", "1-0")]), None, "*");
+    assert_eq!(
+        pgn,
+        r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "A \"quoted\" name"]
+[Black "?"]
+[Result "*"]
+[Annotator "me"]
+
+1. e4 e5 2. Nf3 *
+"#
+    );
+}
+
+#[test]
+fn writes_setup_and_fen_for_other_start_positions() {
+    let chessboard = ChessBoard::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+    let moves = san_moves(&chessboard, "Kd7 e4");
+    // stale SetUp and FEN tags are replaced by the real start position
+    let pgn = write_pgn(&chessboard, &moves, &tags(&[("FEN", "bogus"), ("SetUp", "0"), ("Event", "Endgame")]), None, "*");
+    assert_eq!(
+        pgn,
+        r#"[Event "Endgame"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"]
+
+1... Kd7 2. e4 *
+"#
+    );
+}
+
+#[test]
+fn black_moves_after_a_comment_get_a_number() {
+    let chessboard = ChessBoard::new();
+    let moves = san_moves(&chessboard, "e4 e5 Nf3");
+    let pgn = write_pgn(&chessboard, &moves, &[], Some(&[0.3, -0.25]), "*");
+    assert!(pgn.ends_with("\n1. e4 {[%eval 0.30]} 1... e5 {[%eval -0.25]} 2. Nf3 *\n"), "{}", pgn);
+}
+
+#[test]
+fn wraps_long_movetext() {
+    let chessboard = ChessBoard::new();
+    let moves = san_moves(
+        &chessboard,
+        "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7 Re1 b5 Bb3 d6 c3 O-O h3 Nb8 d4 Nbd7 c4 c6 cxb5 axb5 Nc3 Bb7 Bg5 b4 Nb1 h6 Bh4 c5 dxe5 Nxe4 Bxe7 Qxe7 exd6 Qf6 Nbd2 Nxd6 Nc4 Nxc4 Bxc4 Nb6 Ne5 Rae8",
+    );
+    let evals: Vec<f32> = (0..moves.len()).map(|i| i as f32 / 10.0).collect();
+
+    for evals in [None, Some(evals.as_slice())] {
+        let pgn = write_pgn(&chessboard, &moves, &[], evals, "1/2-1/2");
+        let movetext: Vec<&str> = pgn.split("\n\n").nth(1).unwrap().lines().collect();
+        assert!(movetext.len() > 1);
+        for line in &movetext {
+            assert!(line.len() < 80, "{}", line);
+            assert!(!line.starts_with(' ') && !line.ends_with(' '));
+            // a move number is never separated from its move
+            assert!(!line.ends_with('.'), "{}", line);
+        }
+
+        let games = parse_pgn(&pgn).unwrap();
+        assert_eq!(games[0].game.moves(), moves.as_slice());
+        assert_eq!(games[0].result, "1/2-1/2");
+    }
+}
+
+#[test]
+fn read_games_write_back_the_same_text() {
+    let pgn = r#"[Event "Round trip"]
+[Site "?"]
+[Date "2024.01.31"]
+[Round "1"]
+[White "?"]
+[Black "?"]
+[Result "0-1"]
+[SetUp "1"]
+[FEN "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2"]
+[TimeControl "60+1"]
+
+2... Qh4# 0-1
+"#;
+    assert_eq!(parse_pgn(pgn).unwrap()[0].to_pgn(), pgn);
+}
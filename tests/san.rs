@@ -0,0 +1,131 @@
+// Standard Algebraic Notation
+
+use chess_engine::{ChessBoard, Move, Piece, SanError};
+
+fn board(fen: &str) -> ChessBoard {
+    ChessBoard::from_fen(fen).unwrap()
+}
+
+// the legal move with these coordinates
+fn find(chessboard: &ChessBoard, uci: &str) -> Move {
+    chessboard.parse_uci_move(uci).unwrap()
+}
+
+fn assert_san(chessboard: &ChessBoard, uci: &str, san: &str) {
+    let mov = find(chessboard, uci);
+    assert_eq!(chessboard.move_to_san(mov), san);
+    assert_eq!(chessboard.parse_san(san), Ok(mov));
+}
+
+#[test]
+fn every_move_round_trips() {
+    let chessboard = board("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    for mov in chessboard.legal_moves(None) {
+        assert_eq!(chessboard.parse_san(&chessboard.move_to_san(mov)), Ok(mov));
+    }
+}
+
+#[test]
+fn pawn_and_piece_moves() {
+    let chessboard = ChessBoard::new();
+    assert_san(&chessboard, "e2e4", "e4");
+    assert_san(&chessboard, "g1f3", "Nf3");
+}
+
+#[test]
+fn disambiguation_by_file() {
+    // knights on b8 and f6 can both go to d7
+    let chessboard = board("rnbqkb1r/ppp1pppp/5n2/3p4/3P4/8/PPP1PPPP/RNBQKBNR b KQkq - 0 1");
+    assert_san(&chessboard, "b8d7", "Nbd7");
+    assert_san(&chessboard, "f6d7", "Nfd7");
+}
+
+#[test]
+fn disambiguation_by_rank() {
+    let chessboard = board("4k3/8/8/1N6/8/1N6/8/4K3 w - - 0 1");
+    assert_san(&chessboard, "b3d4", "N3d4");
+    assert_san(&chessboard, "b5d4", "N5d4");
+}
+
+#[test]
+fn disambiguation_by_square() {
+    // one queen shares the file with a1, the other the rank
+    let chessboard = board("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1");
+    assert_san(&chessboard, "a1b2", "Qa1b2");
+    assert_san(&chessboard, "a3b2", "Q3b2");
+    assert_san(&chessboard, "c1b2", "Qcb2");
+}
+
+#[test]
+fn promotions() {
+    let chessboard = board("3r4/4Pk2/8/8/8/8/8/4K3 w - - 0 1");
+    let knight_promotion = chessboard.legal_moves(None).into_iter().find(|mov| mov.to_string() == "e7d8n").unwrap();
+    assert_eq!(knight_promotion.promotion, Some(Piece::Knight));
+    assert_eq!(chessboard.move_to_san(knight_promotion), "exd8=N+");
+    assert_eq!(chessboard.parse_san("exd8=N+"), Ok(knight_promotion));
+    assert_eq!(chessboard.parse_san("exd8N"), Ok(knight_promotion));
+    assert_san(&chessboard, "e7e8q", "e8=Q+");
+}
+
+#[test]
+fn castling() {
+    let chessboard = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert_san(&chessboard, "e1g1", "O-O");
+    assert_san(&chessboard, "e1c1", "O-O-O");
+    assert_eq!(chessboard.parse_san("0-0-0"), Ok(find(&chessboard, "e1c1")));
+}
+
+#[test]
+fn check_and_mate_suffixes() {
+    let chessboard = board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    assert_san(&chessboard, "a1a8", "Ra8#");
+    assert_san(&chessboard, "a1a7", "Ra7");
+    let chessboard = board("6k1/8/8/8/8/8/8/R5K1 w - - 0 1");
+    assert_san(&chessboard, "a1a8", "Ra8+");
+    // marks and annotations are ignored when parsing
+    assert_eq!(chessboard.parse_san("Ra8!?"), Ok(find(&chessboard, "a1a8")));
+}
+
+#[test]
+fn captures() {
+    let chessboard = board("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+    assert_san(&chessboard, "e4d5", "exd5");
+    assert_eq!(chessboard.parse_san("e:d5"), Ok(find(&chessboard, "e4d5")));
+}
+
+#[test]
+fn capture_mark_has_to_match() {
+    let chessboard = ChessBoard::new();
+    assert_eq!(chessboard.parse_san("Nxf3"), Err(SanError::IllegalMove("Nxf3".to_string())));
+    assert_eq!(chessboard.parse_san("exe4"), Err(SanError::IllegalMove("exe4".to_string())));
+    let chessboard = board("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+    assert_eq!(chessboard.parse_san("d5"), Err(SanError::IllegalMove("d5".to_string())));
+}
+
+#[test]
+fn ambiguous_moves() {
+    let chessboard = board("4k3/8/8/1N6/8/1N6/8/4K3 w - - 0 1");
+    assert_eq!(chessboard.parse_san("Nd4"), Err(SanError::AmbiguousMove("Nd4".to_string())));
+    let chessboard = board("rnbqkb1r/ppp1pppp/5n2/3p4/3P4/8/PPP1PPPP/RNBQKBNR b KQkq - 0 1");
+    assert_eq!(chessboard.parse_san("Nd7"), Err(SanError::AmbiguousMove("Nd7".to_string())));
+}
+
+#[test]
+fn illegal_moves() {
+    let chessboard = ChessBoard::new();
+    assert_eq!(chessboard.parse_san("Qh5"), Err(SanError::IllegalMove("Qh5".to_string())));
+    assert_eq!(chessboard.parse_san("e5"), Err(SanError::IllegalMove("e5".to_string())));
+    assert_eq!(chessboard.parse_san("O-O"), Err(SanError::IllegalMove("O-O".to_string())));
+    // right piece, wrong disambiguation
+    assert_eq!(chessboard.parse_san("Ncf3"), Err(SanError::IllegalMove("Ncf3".to_string())));
+}
+
+#[test]
+fn syntax_errors() {
+    let chessboard = ChessBoard::new();
+    assert_eq!(chessboard.parse_san(""), Err(SanError::Empty));
+    assert_eq!(chessboard.parse_san("+"), Err(SanError::Empty));
+    for san in ["e9", "Nz3", "Xe4", "e8=K", "e8=", "N", "Nfgf3", "e4e5e6"] {
+        assert_eq!(chessboard.parse_san(san), Err(SanError::InvalidSyntax(san.to_string())), "{}", san);
+    }
+}
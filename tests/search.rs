@@ -0,0 +1,40 @@
+// Search limits and draw scoring
+
+use std::sync::atomic::AtomicBool;
+
+use chess_engine::{search, Game, SearchLimits};
+
+// white is a queen for a knight down
+const KNIGHT_AGAINST_QUEEN: &str = "q6k/8/8/8/8/8/8/6NK w - - 0 1";
+
+fn search_to_depth(game: &Game, depth: u32) -> (f32, String) {
+    let limits = SearchLimits { depth: Some(depth), ..Default::default() };
+    let result = search(game, limits, &AtomicBool::new(false), |_| {}).unwrap();
+    (result.score, result.pv[0].to_string())
+}
+
+#[test]
+fn node_limit_is_exact() {
+    let game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+    for nodes in [1, 50, 3000] {
+        let limits = SearchLimits { nodes: Some(nodes), ..Default::default() };
+        let result = search(&game, limits, &AtomicBool::new(false), |_| {}).unwrap();
+        assert_eq!(result.nodes, nodes);
+        assert!(game.legal_moves().contains(&result.pv[0]));
+    }
+}
+
+#[test]
+fn repeating_the_position_is_a_draw() {
+    let fresh = Game::from_fen(KNIGHT_AGAINST_QUEEN).unwrap();
+    let (score, _) = search_to_depth(&fresh, 2);
+    assert!(score < -5.0, "score {}", score);
+
+    // the same position, but Nf3 now repeats the position after the first Nf3
+    let mut repeated = fresh.clone();
+    for text in ["g1f3", "a8b8", "f3g1", "b8a8"] {
+        let mov = repeated.board().parse_uci_move(text).unwrap();
+        repeated.play(mov).unwrap();
+    }
+    assert_eq!(search_to_depth(&repeated, 2), (0.0, "g1f3".to_string()));
+}
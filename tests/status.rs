@@ -0,0 +1,98 @@
+// Game-ending positions judged by the board alone
+
+use chess_engine::{ChessBoard, Color, GameStatus};
+
+fn board(fen: &str) -> ChessBoard {
+    ChessBoard::from_fen(fen).unwrap()
+}
+
+#[test]
+fn checkmate() {
+    // back rank mate
+    let chessboard = board("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1");
+    assert!(chessboard.is_in_check());
+    assert!(chessboard.is_checkmate());
+    assert!(!chessboard.is_stalemate());
+    assert_eq!(chessboard.status(), GameStatus::Checkmate { winner: Color::White });
+    assert_eq!(chessboard.status().result(), "1-0");
+
+    // fool's mate
+    let chessboard = board("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+    assert_eq!(chessboard.status(), GameStatus::Checkmate { winner: Color::Black });
+    assert_eq!(chessboard.status().result(), "0-1");
+}
+
+#[test]
+fn check_that_can_be_answered_is_no_mate() {
+    // the king can take the unprotected rook
+    let chessboard = board("6k1/5pR1/8/8/8/8/8/6K1 b - - 0 1");
+    assert!(chessboard.is_in_check());
+    assert!(!chessboard.is_checkmate());
+    assert_eq!(chessboard.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn stalemate() {
+    let chessboard = board("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    assert!(!chessboard.is_in_check());
+    assert!(chessboard.is_stalemate());
+    assert!(!chessboard.is_checkmate());
+    assert_eq!(chessboard.status(), GameStatus::Stalemate);
+    assert_eq!(chessboard.status().result(), "1/2-1/2");
+
+    // a pawn that can't move doesn't help
+    let chessboard = board("k7/P7/1K6/8/8/8/8/8 b - - 0 1");
+    assert_eq!(chessboard.status(), GameStatus::Stalemate);
+}
+
+#[test]
+fn insufficient_material() {
+    for fen in [
+        // bare kings
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        // a single knight or bishop
+        "4k3/8/8/8/8/8/8/3NK3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/2B1K3 b - - 0 1",
+        "4kb2/8/8/8/8/8/8/4K3 w - - 0 1",
+        // bishops on the same color only, whoever they belong to
+        "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/B1B1K3 w - - 0 1",
+        "1b1bkb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+    ] {
+        let chessboard = board(fen);
+        assert!(chessboard.is_insufficient_material(), "{}", fen);
+        assert_eq!(chessboard.status(), GameStatus::InsufficientMaterial, "{}", fen);
+    }
+}
+
+#[test]
+fn sufficient_material() {
+    for fen in [
+        // two knights, knight against knight, the bishop pair, bishops on
+        // opposite colors, bishop against knight: a mate exists in all of
+        // them, even if it can't be forced
+        "4k3/8/8/8/8/8/8/2NNK3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N1NK3 b - - 0 1",
+        "3nk3/8/8/8/8/8/8/3NK3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/2BBK3 w - - 0 1",
+        "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "3bk3/8/8/8/8/8/8/3NK3 w - - 0 1",
+        // anything with a pawn, rook or queen
+        "4k3/8/8/8/8/8/P7/4K3 w - - 0 1",
+        "4k3/p7/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        "3qk3/8/8/8/8/8/8/4K3 w - - 0 1",
+    ] {
+        let chessboard = board(fen);
+        assert!(!chessboard.is_insufficient_material(), "{}", fen);
+        assert_eq!(chessboard.status(), GameStatus::Ongoing, "{}", fen);
+    }
+}
+
+#[test]
+fn mate_comes_before_the_fifty_move_rule() {
+    let chessboard = board("R5k1/5ppp/8/8/8/8/8/6K1 b - - 120 90");
+    assert_eq!(chessboard.status(), GameStatus::Checkmate { winner: Color::White });
+    let chessboard = board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 120 90");
+    assert_eq!(chessboard.status(), GameStatus::FiftyMoveRule);
+}
@@ -0,0 +1,183 @@
+// UCI long algebraic notation
+
+use chess_engine::{run_uci, ChessBoard, Game, Move, Piece, UciMoveError};
+
+fn board(fen: &str) -> ChessBoard {
+    ChessBoard::from_fen(fen).unwrap()
+}
+
+fn invalid(text: &str) -> Result<Move, UciMoveError> {
+    Err(UciMoveError::InvalidSyntax(text.to_string()))
+}
+
+fn illegal(text: &str) -> Result<Move, UciMoveError> {
+    Err(UciMoveError::IllegalMove(text.to_string()))
+}
+
+#[test]
+fn every_move_round_trips() {
+    let chessboard = board("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    for mov in chessboard.legal_moves(None) {
+        assert_eq!(chessboard.parse_uci_move(&mov.to_uci()), Ok(mov));
+    }
+}
+
+#[test]
+fn quiet_moves_and_captures() {
+    let chessboard = board("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+    let push = chessboard.parse_uci_move("e4e5").unwrap();
+    assert_eq!((push.from, push.to, push.is_capture()), (28, 36, false));
+    let capture = chessboard.parse_uci_move("e4d5").unwrap();
+    assert_eq!((capture.from, capture.to, capture.is_capture()), (28, 35, true));
+}
+
+#[test]
+fn promotions() {
+    let chessboard = board("3r4/4Pk2/8/8/8/8/8/4K3 w - - 0 1");
+    for (text, piece) in [("e7e8q", Piece::Queen), ("e7e8r", Piece::Rook), ("e7e8b", Piece::Bishop), ("e7d8n", Piece::Knight)] {
+        let mov = chessboard.parse_uci_move(text).unwrap();
+        assert_eq!(mov.promotion, Some(piece));
+        assert_eq!(mov.to_uci(), text);
+    }
+    // the promotion piece is required, and only on promotions
+    assert_eq!(chessboard.parse_uci_move("e7e8"), illegal("e7e8"));
+    assert_eq!(chessboard.parse_uci_move("e1e2q"), illegal("e1e2q"));
+    // no promoting to a king or a pawn, and the piece is lowercase
+    assert_eq!(chessboard.parse_uci_move("e7e8k"), invalid("e7e8k"));
+    assert_eq!(chessboard.parse_uci_move("e7e8p"), invalid("e7e8p"));
+    assert_eq!(chessboard.parse_uci_move("e7e8Q"), invalid("e7e8Q"));
+}
+
+#[test]
+fn castling_is_the_king_move() {
+    let chessboard = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    let short = chessboard.parse_uci_move("e1g1").unwrap();
+    assert!(short.is_castle());
+    assert!(chessboard.parse_uci_move("e1c1").unwrap().is_castle());
+    // king takes own rook is how Chess960 writes it, not standard UCI
+    assert_eq!(chessboard.parse_uci_move("e1h1"), illegal("e1h1"));
+
+    let chessboard = board("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1");
+    assert!(chessboard.parse_uci_move("e8g8").unwrap().is_castle());
+    assert!(chessboard.parse_uci_move("e8c8").unwrap().is_castle());
+}
+
+#[test]
+fn illegal_moves() {
+    let chessboard = ChessBoard::new();
+    assert_eq!(chessboard.parse_uci_move("e2e5"), illegal("e2e5"));
+    assert_eq!(chessboard.parse_uci_move("e7e5"), illegal("e7e5"));
+    assert_eq!(chessboard.parse_uci_move("e4e5"), illegal("e4e5"));
+    assert_eq!(chessboard.parse_uci_move("e1g1"), illegal("e1g1"));
+    assert_eq!(chessboard.parse_uci_move("0000"), invalid("0000"));
+
+    // the king has to get out of check, and not into another one
+    let chessboard = board("4k3/8/8/8/8/8/8/r3K3 w - - 0 1");
+    assert!(chessboard.parse_uci_move("e1e2").is_ok());
+    assert_eq!(chessboard.parse_uci_move("e1f1"), illegal("e1f1"));
+    let chessboard = board("4k3/8/8/8/8/8/r7/4K3 w - - 0 1");
+    assert_eq!(chessboard.parse_uci_move("e1e2"), illegal("e1e2"));
+}
+
+#[test]
+fn syntax_errors() {
+    let chessboard = ChessBoard::new();
+    for text in ["", "e2", "e2e", "e2e4qq", "z1a1", "a9a1", "e2-e4", "E2E4", "Nf3", "e2e4+"] {
+        assert_eq!(chessboard.parse_uci_move(text), invalid(text), "{}", text);
+    }
+}
+
+#[test]
+fn errors_name_the_move() {
+    let chessboard = ChessBoard::new();
+    assert_eq!(chessboard.parse_uci_move("e2e5").unwrap_err().to_string(), "'e2e5' is not a legal move in this position");
+    assert_eq!(chessboard.parse_uci_move("e2e").unwrap_err().to_string(), "'e2e' is not a move in long algebraic notation");
+}
+
+// PROTOCOL
+
+// the engine's answers to the commands, one per line
+fn uci(commands: &str) -> Vec<String> {
+    let output = run_uci(commands.as_bytes(), Vec::new());
+    String::from_utf8(output).unwrap().lines().map(str::to_string).collect()
+}
+
+fn best_move(lines: &[String]) -> &str {
+    let best_moves: Vec<&String> = lines.iter().filter(|line| line.starts_with("bestmove ")).collect();
+    assert_eq!(best_moves.len(), 1, "{:?}", lines);
+    &best_moves[0]["bestmove ".len()..]
+}
+
+#[test]
+fn handshake() {
+    let lines = uci("uci\nisready\n");
+    assert!(lines[0].starts_with("id name "));
+    assert!(lines.contains(&"option name Move Overhead type spin default 10 min 0 max 5000".to_string()));
+    assert_eq!(lines[lines.len() - 2..], ["uciok", "readyok"]);
+}
+
+#[test]
+fn searches_the_position_after_the_moves() {
+    let lines = uci("position startpos moves e2e4 e7e5 g1f3\ngo depth 2\n");
+    assert!(lines.iter().any(|line| line.starts_with("info depth 2 ")), "{:?}", lines);
+
+    let mut game = Game::new();
+    for text in ["e2e4", "e7e5", "g1f3"] {
+        game.play(game.board().parse_uci_move(text).unwrap()).unwrap();
+    }
+    assert!(game.board().parse_uci_move(best_move(&lines)).is_ok());
+}
+
+#[test]
+fn position_from_fen() {
+    let lines = uci("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 2\n");
+    assert_eq!(best_move(&lines), "a1a8");
+    assert!(lines.iter().any(|line| line.contains(" score mate 1 ")), "{:?}", lines);
+
+    // fen followed by moves
+    let lines = uci("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1 moves a1a2 g8h8\ngo depth 2\n");
+    assert_eq!(best_move(&lines), "a2a8");
+}
+
+#[test]
+fn moves_stop_at_the_first_illegal_one() {
+    let lines = uci("position startpos moves e2e4 e2e4 e7e5\ngo depth 1\n");
+    assert_eq!(lines[0], "info string 'e2e4' is not a legal move in this position");
+    // black is to move after 1. e4
+    let chessboard = ChessBoard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    assert!(chessboard.parse_uci_move(best_move(&lines)).is_ok());
+}
+
+#[test]
+fn invalid_position_keeps_the_previous_one() {
+    let lines = uci("position startpos moves e2e4\nposition fen 8/8/8/8/8/8/8/8 w - - 0 1\nposition\ngo depth 1\n");
+    assert!(lines[0].starts_with("info string invalid position: "), "{:?}", lines);
+    assert_eq!(lines[1], "info string invalid position: expected startpos or fen");
+    let chessboard = ChessBoard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    assert!(chessboard.parse_uci_move(best_move(&lines)).is_ok());
+}
+
+#[test]
+fn no_legal_moves() {
+    let lines = uci("position fen R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1\ngo depth 3\n");
+    assert_eq!(best_move(&lines), "0000");
+}
+
+#[test]
+fn set_option() {
+    assert!(uci("setoption name Move Overhead value 50\n").is_empty());
+    assert_eq!(uci("setoption name Move Overhead value soon\n"), ["info string invalid value 'soon' for option Move Overhead"]);
+    assert_eq!(uci("setoption name Hash value 16\n"), ["info string unknown option 'Hash'"]);
+}
+
+#[test]
+fn clock_gives_a_move_in_time() {
+    let lines = uci("setoption name Move Overhead value 0\nposition startpos\ngo wtime 300 btime 300 winc 0 binc 0\n");
+    assert!(ChessBoard::new().parse_uci_move(best_move(&lines)).is_ok());
+}
+
+#[test]
+fn quit_ends_the_session() {
+    assert_eq!(uci("isready\nquit\nisready\n"), ["readyok"]);
+    assert_eq!(uci("hello\n"), ["info string unknown command 'hello'"]);
+}